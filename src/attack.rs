@@ -0,0 +1,57 @@
+use crate::game::{LockResult, TSpin};
+
+// how many garbage lines a lock sends to the opponent
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttackTable {
+    // indexed by lines cleared
+    pub lines: [i32; 5],
+    pub t_spin: [i32; 4],
+    pub t_spin_mini: [i32; 3],
+    // added on top of a difficult clear that continues a back-to-back chain
+    pub back_to_back: i32,
+    // indexed by the combo counter, the last entry is used for every longer combo
+    pub combo: Vec<i32>,
+    // replaces everything else when the lock clears the whole board
+    pub perfect_clear: i32,
+}
+
+impl AttackTable {
+    pub fn guideline() -> Self {
+        Self {
+            lines: [0, 0, 1, 2, 4],
+            t_spin: [0, 2, 4, 6],
+            t_spin_mini: [0, 0, 1],
+            back_to_back: 1,
+            combo: vec![0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 4, 5],
+            perfect_clear: 10,
+        }
+    }
+
+    pub fn attack(&self, lock: &LockResult) -> i32 {
+        if lock.lines_cleared == 0 { return 0 }
+        if lock.perfect_clear { return self.perfect_clear }
+
+        let lines = usize::min(lock.lines_cleared, 4);
+
+        let clear_attack = match lock.t_spin {
+            TSpin::None => self.lines[lines],
+            TSpin::Mini => self.t_spin_mini[usize::min(lines, 2)],
+            TSpin::Full => self.t_spin[usize::min(lines, 3)],
+        };
+
+        let back_to_back_attack = if lock.back_to_back { self.back_to_back } else { 0 };
+
+        let combo_attack = match self.combo.last() {
+            Some(last) => *self.combo.get(lock.combo.max(0) as usize).unwrap_or(last),
+            None => 0
+        };
+
+        clear_attack + back_to_back_attack + combo_attack
+    }
+}
+
+impl Default for AttackTable {
+    fn default() -> Self {
+        Self::guideline()
+    }
+}
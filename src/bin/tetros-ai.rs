@@ -1,36 +1,36 @@
 use std::sync::{mpsc, Mutex, Arc};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::thread;
+use std::env;
 use sdl2::pixels::Color;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
 use tetros::game::Game;
 use tetros::controls;
-use tetros::bot::{Bot, Weigths};
+use tetros::bot::{self, Bot};
 use tetros::gui::GUI;
+use tetros::cli::flag;
 
 fn main() {
-    let game = Game::new();
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let game = match flag(&args, "--seed") {
+        Some(seed) => Game::with_seed(seed.parse().expect("invalid seed")),
+        None => Game::new()
+    };
 
     let sdl_context = sdl2::init().unwrap();
     let ttf_context = sdl2::ttf::init().unwrap();
     let mut gui = GUI::build(&sdl_context, &ttf_context, game, "Tetros AI (evaluation)");
 
     let bot = Bot {
-        weights: Weigths {
-            holes_penalty: 16000,
-            bumpiness_penalty: 6,
-            height_penalty: 2,
-            line_clearing: [-90, -70, -50, 800]
-        },
+        weights: bot::TUNED_WEIGHTS,
         depth: 1
     };
 
     let next_move: Arc<Mutex<Option<(bool, i32, usize)>>> = Arc::new(Mutex::new(None));
     let (mission_tx, mission_rx) = mpsc::channel();
 
-    let mut hold_debounce = false;
-
     {
         let next_move = Arc::clone(&next_move);
         thread::spawn(move || {
@@ -42,34 +42,24 @@ fn main() {
     }
 
     let mut slow = false;
+    let mut last_frame = Instant::now();
 
     'running: loop {
-        gui.game.next_frame();
+        let frame_delta = last_frame.elapsed();
+        last_frame = Instant::now();
+
+        gui.game.next_frame(frame_delta);
 
         if let Ok(mut next_move) = next_move.try_lock() {
             if let Some(next_move_unwrapped) = *next_move {
-                if !hold_debounce && next_move_unwrapped.0 { controls::hold_tetro(&mut gui.game) };
-                hold_debounce = true;
-
-                let rotate_times = next_move_unwrapped.2 as i32 - gui.game.dropping_tetro.rotation as i32;
-                let shift_amount = next_move_unwrapped.1 - gui.game.dropping_tetro.cord.0;
-
-                if shift_amount == 0 && rotate_times == 0 {
+                if bot::move_towards(&mut gui.game, next_move_unwrapped) {
                     controls::hard_drop(&mut gui.game);
                     if slow { thread::sleep(Duration::from_millis(750)); };
                     *next_move = None;
-                } else {
-                    if shift_amount != 0 {
-                        controls::shift_tetro(&mut gui.game, if shift_amount.is_positive() { 1 } else { -1 });
-                    }
-                    if rotate_times != 0 {
-                        controls::rotate_tetro(&mut gui.game, rotate_times)
-                    } 
-
-                    if slow { thread::sleep(Duration::from_millis(35)); };
+                } else if slow {
+                    thread::sleep(Duration::from_millis(35));
                 }
             } else {
-                hold_debounce = false;
                 mission_tx.send(gui.game.clone()).unwrap();
             }
         }
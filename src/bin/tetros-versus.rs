@@ -0,0 +1,213 @@
+use std::sync::{mpsc, Mutex, Arc};
+use std::time::{Duration, Instant};
+use std::thread;
+use std::env;
+use sdl2::pixels::Color;
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use rand::{thread_rng, Rng};
+use tetros::game::Game;
+use tetros::controls;
+use tetros::versus;
+use tetros::bot::{self, Bot};
+use tetros::gui::GUI;
+use tetros::cli::flag;
+
+// whether to hold, the column and the rotation, as worked out by `Bot::best_move`
+type Move = (bool, i32, usize);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Action {
+    ShiftLeft,
+    ShiftRight,
+    RotateLeft,
+    RotateRight,
+    Rotate180,
+    SoftDrop,
+    HardDrop,
+    Hold,
+}
+
+impl Action {
+    // player one plays on the left of the keyboard and player two on the right
+    fn from_keycode(keycode: Keycode) -> Option<(usize, Action)> {
+        match keycode {
+            Keycode::A => Some((0, Action::ShiftLeft)),
+            Keycode::D => Some((0, Action::ShiftRight)),
+            Keycode::Q => Some((0, Action::RotateLeft)),
+            Keycode::E => Some((0, Action::RotateRight)),
+            Keycode::W => Some((0, Action::Rotate180)),
+            Keycode::S => Some((0, Action::SoftDrop)),
+            Keycode::Space => Some((0, Action::HardDrop)),
+            Keycode::F => Some((0, Action::Hold)),
+            Keycode::J => Some((1, Action::ShiftLeft)),
+            Keycode::L => Some((1, Action::ShiftRight)),
+            Keycode::U => Some((1, Action::RotateLeft)),
+            Keycode::O => Some((1, Action::RotateRight)),
+            Keycode::I => Some((1, Action::Rotate180)),
+            Keycode::K => Some((1, Action::SoftDrop)),
+            Keycode::Return => Some((1, Action::HardDrop)),
+            Keycode::H => Some((1, Action::Hold)),
+            _ => None
+        }
+    }
+
+    fn apply(&self, game: &mut Game) {
+        match self {
+            Action::ShiftLeft => controls::shift_tetro(game, -1),
+            Action::ShiftRight => controls::shift_tetro(game, 1),
+            Action::RotateLeft => { controls::rotate_tetro(game, -1); },
+            Action::RotateRight => { controls::rotate_tetro(game, 1); },
+            Action::Rotate180 => { controls::rotate_tetro(game, 2); },
+            Action::SoftDrop => game.is_soft_dropping = true,
+            Action::HardDrop => controls::hard_drop(game),
+            Action::Hold => controls::hold_tetro(game),
+        }
+    }
+
+    fn is_repeating(&self) -> bool {
+        matches!(self, Action::ShiftLeft | Action::ShiftRight | Action::RotateLeft | Action::RotateRight | Action::Rotate180)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Key(Keycode, Instant);
+
+fn players<'a>(gui: &'a mut GUI) -> [&'a mut Game; 2] {
+    [&mut gui.game, gui.opponent.as_mut().unwrap()]
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    // both players get the same pieces
+    let seed = match flag(&args, "--seed") {
+        Some(seed) => seed.parse().expect("invalid seed"),
+        None => thread_rng().gen()
+    };
+
+    // with `--bot` the bot takes the place of player two, placing at most `--pps` pieces a second
+    let bot_enabled = args.iter().any(|arg| arg == "--bot");
+    let bot_placement_interval = match flag(&args, "--pps") {
+        Some(pps) => {
+            let pps: f64 = pps.parse().expect("invalid pieces per second");
+            assert!(pps > 0. && pps.is_finite(), "pieces per second must be above 0");
+
+            Duration::from_secs_f64(1. / pps)
+        },
+        None => Duration::from_secs(1)
+    };
+
+    let sdl_context = sdl2::init().unwrap();
+    let ttf_context = sdl2::ttf::init().unwrap();
+    let mut gui = GUI::build(&sdl_context, &ttf_context, Game::with_seed(seed), "Tetros versus")
+        .with_opponent(Game::with_seed(seed));
+
+    let bot = Bot {
+        weights: bot::TUNED_WEIGHTS,
+        depth: 1
+    };
+
+    // the best move along with the amount of pieces the bot's game had locked when it was asked for
+    let next_move: Arc<Mutex<Option<(u32, Move)>>> = Arc::new(Mutex::new(None));
+    let (mission_tx, mission_rx) = mpsc::channel::<Game>();
+
+    if bot_enabled {
+        let next_move = Arc::clone(&next_move);
+        thread::spawn(move || {
+            for game in mission_rx {
+                let mut next_move = next_move.lock().unwrap();
+                *next_move = bot.best_move(&game).map(|best_move| (game.pieces, best_move));
+            }
+        });
+    }
+
+    let mut last_bot_placement = Duration::ZERO;
+    let mut requested_pieces = None;
+
+    let mut event_pump = gui.sdl_context.event_pump().unwrap();
+
+    let mut keys_down: Vec<Key> = vec![];
+    let mut last_frame = Instant::now();
+
+    'running: loop {
+        let frame_delta = last_frame.elapsed();
+        last_frame = Instant::now();
+
+        for game in players(&mut gui) {
+            game.next_frame(frame_delta);
+        }
+
+        if bot_enabled && gui.opponent.as_ref().unwrap().is_playing {
+            let bot_game = gui.opponent.as_mut().unwrap();
+
+            if let Ok(mut next_move) = next_move.try_lock() {
+                // gravity and lock delay can lock the tetro while the pieces per second cap holds the
+                // bot back, which leaves a move meant for a tetro that isn't dropping anymore
+                if next_move.is_some_and(|(pieces, _)| pieces != bot_game.pieces) {
+                    *next_move = None;
+                }
+
+                match *next_move {
+                    Some((_, next_move_unwrapped)) => {
+                        let is_in_place = bot::move_towards(bot_game, next_move_unwrapped);
+
+                        if is_in_place && bot_game.elapsed(last_bot_placement) >= bot_placement_interval {
+                            controls::hard_drop(bot_game);
+                            last_bot_placement = bot_game.time;
+                            *next_move = None;
+                        }
+                    },
+                    None if requested_pieces != Some(bot_game.pieces) => {
+                        requested_pieces = Some(bot_game.pieces);
+                        mission_tx.send(bot_game.clone()).unwrap();
+                    },
+                    None => ()
+                }
+            }
+        }
+
+        let [player, opponent] = players(&mut gui);
+        versus::update(player, opponent);
+
+        gui.canvas.clear();
+        gui.draw();
+
+        gui.canvas.set_draw_color(Color::RGB(52, 73, 94));
+        gui.canvas.present();
+
+        for key in &keys_down {
+            let Some((player, action)) = Action::from_keycode(key.0) else { continue };
+            if bot_enabled && player == 1 { continue }
+
+            if action.is_repeating() && key.1.elapsed().as_millis() > 350 {
+                action.apply(players(&mut gui)[player]);
+            }
+        }
+
+        for event in event_pump.poll_iter() {
+            match event {
+                Event::Quit {..} => {
+                    break 'running
+                },
+                Event::KeyDown { keycode: Some(keycode), .. } if !keys_down.iter().any(|key| key.0 == keycode) => {
+                    let Some((player, action)) = Action::from_keycode(keycode) else { continue };
+                    if bot_enabled && player == 1 { continue }
+
+                    keys_down.push(Key(keycode, Instant::now()));
+                    action.apply(players(&mut gui)[player]);
+                },
+                Event::KeyUp { keycode: Some(keycode), .. } => {
+                    keys_down.retain(|key| key.0 != keycode);
+
+                    if let Some((player, Action::SoftDrop)) = Action::from_keycode(keycode) {
+                        players(&mut gui)[player].is_soft_dropping = false;
+                    }
+                },
+                _ => ()
+            }
+        }
+
+        thread::sleep(Duration::from_millis(1000 / 30));
+    }
+}
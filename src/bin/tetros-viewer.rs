@@ -45,11 +45,8 @@ fn main() {
         gui.canvas.present();
 
         for event in event_pump.poll_iter() {
-            match event {
-                Event::Quit {..} => {
-                    break 'running
-                },
-                _ => ()
+            if let Event::Quit {..} = event {
+                break 'running
             }
         }
 
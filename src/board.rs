@@ -0,0 +1,249 @@
+use crate::{Cord, GAME_WIDTH, GAME_HEIGHT, GAME_BUFFER_HEIGHT};
+use crate::tetros::{GameTetro, TetroType};
+
+pub const MAX_BOARD_WIDTH: i32 = u32::BITS as i32;
+
+// the visible playfield spans y 0 to `height`, the buffer rows above it have negative y cords
+//
+// every row is kept as a bitmask with bit x set when the block in column x is filled, which is
+// all collision and line clearing look at, while the tetro types are only kept around for drawing
+// and filled blocks without a tetro type are garbage
+#[derive(Clone, Debug)]
+pub struct Board {
+    pub width: i32,
+    pub height: i32,
+    pub buffer_height: i32,
+    rows: Vec<u32>,
+    blocks: Vec<Option<TetroType>>
+}
+
+impl Board {
+    pub fn new(width: i32, height: i32, buffer_height: i32) -> Self {
+        assert!(width > 0 && width <= MAX_BOARD_WIDTH, "board width must be between 1 and {MAX_BOARD_WIDTH}");
+        assert!(height > 0, "board height must be at least 1");
+        // tetros spawn two rows above the visible board, see `start_pos`
+        assert!(buffer_height >= 2, "board buffer height must be at least 2");
+
+        Self {
+            width,
+            height,
+            buffer_height,
+            rows: vec![0; (height + buffer_height) as usize],
+            blocks: vec![None; (width * (height + buffer_height)) as usize]
+        }
+    }
+
+    pub fn rows(&self) -> i32 {
+        self.height + self.buffer_height
+    }
+
+    pub fn full_row(&self) -> u32 {
+        u32::MAX >> (MAX_BOARD_WIDTH - self.width)
+    }
+
+    pub fn is_inside(&self, cord: Cord) -> bool {
+        cord.0 >= 0 && cord.0 < self.width && cord.1 >= -self.buffer_height && cord.1 < self.height
+    }
+
+    fn row_index(&self, y: i32) -> usize {
+        (y + self.buffer_height) as usize
+    }
+
+    fn block_index(&self, cord: Cord) -> usize {
+        (cord.0 + (cord.1 + self.buffer_height) * self.width) as usize
+    }
+
+    // the bitmask of filled blocks in row `y`, rows outside the board count as full
+    pub fn row(&self, y: i32) -> u32 {
+        if y < -self.buffer_height || y >= self.height { return self.full_row() }
+
+        self.rows[self.row_index(y)]
+    }
+
+    pub fn get(&self, cord: Cord) -> Option<TetroType> {
+        if !self.is_inside(cord) { return None }
+
+        self.blocks[self.block_index(cord)]
+    }
+
+    pub fn set(&mut self, cord: Cord, block: Option<TetroType>) {
+        let row_index = self.row_index(cord.1);
+        let block_index = self.block_index(cord);
+
+        if block.is_some() {
+            self.rows[row_index] |= 1 << cord.0;
+        } else {
+            self.rows[row_index] &= !(1 << cord.0);
+        }
+        self.blocks[block_index] = block;
+    }
+
+    pub fn set_garbage(&mut self, cord: Cord) {
+        let row_index = self.row_index(cord.1);
+        let block_index = self.block_index(cord);
+
+        self.rows[row_index] |= 1 << cord.0;
+        self.blocks[block_index] = None;
+    }
+
+    pub fn is_garbage(&self, cord: Cord) -> bool {
+        self.is_inside(cord) && self.is_filled(cord) && self.get(cord).is_none()
+    }
+
+    // anything outside the board counts as filled
+    pub fn is_filled(&self, cord: Cord) -> bool {
+        if !self.is_inside(cord) { return true }
+
+        self.row(cord.1) >> cord.0 & 1 == 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.iter().all(|row| *row == 0)
+    }
+
+    // tetros spawn in the buffer rows right above the visible playfield
+    pub fn start_pos(&self, tetro_type: TetroType) -> Cord {
+        Cord((self.width - tetro_type.shape_size()) / 2, -2)
+    }
+
+    pub fn is_tetro_colliding(&self, tetro: GameTetro) -> bool {
+        let state = tetro.tetro_type.rotation_state(tetro.rotation);
+
+        for shape_row in state.min.1..=state.max.1 {
+            let row_shape = state.rows[shape_row as usize] as u64;
+
+            // shift the row of the shape into board columns, any bit pushed past either wall collides
+            let row_mask = if tetro.cord.0 < 0 {
+                if row_shape & ((1 << -tetro.cord.0) - 1) != 0 { return true }
+                row_shape >> -tetro.cord.0
+            } else {
+                row_shape << tetro.cord.0
+            };
+
+            if row_mask & !(self.full_row() as u64) != 0 { return true }
+            if row_mask & self.row(tetro.cord.1 + shape_row) as u64 != 0 { return true }
+        }
+
+        false
+    }
+
+    pub fn petrify_tetro(&mut self, tetro: GameTetro) {
+        for cord in tetro.cells() {
+            self.set(cord, Some(tetro.tetro_type));
+        }
+    }
+
+    pub fn clear_lines(&mut self) -> usize {
+        let full_row = self.full_row();
+        let width = self.width as usize;
+
+        let mut lines_removed = 0;
+        for row in (0..self.rows.len()).rev() {
+            if self.rows[row] == full_row {
+                lines_removed += 1;
+            } else if lines_removed != 0 {
+                self.rows[row + lines_removed] = self.rows[row];
+                self.blocks.copy_within(row * width..(row + 1) * width, (row + lines_removed) * width);
+            }
+        }
+
+        self.rows[..lines_removed].fill(0);
+        self.blocks[..lines_removed * width].fill(None);
+
+        lines_removed
+    }
+
+    // shifts the whole stack up and fills the bottom with a garbage row for every hole, listed
+    // from the bottom up, returns false when filled blocks got pushed off the top of the buffer
+    pub fn push_garbage(&mut self, holes: &[i32]) -> bool {
+        let rows_amount = usize::min(holes.len(), self.rows.len());
+        let width = self.width as usize;
+
+        let pushed_out = self.rows[..rows_amount].iter().any(|row| *row != 0);
+
+        self.rows.copy_within(rows_amount.., 0);
+        self.blocks.copy_within(rows_amount * width.., 0);
+
+        for (i, hole) in holes.iter().take(rows_amount).enumerate() {
+            let row = self.rows.len() - 1 - i;
+
+            self.rows[row] = self.full_row() & !(1 << hole);
+            self.blocks[row * width..(row + 1) * width].fill(None);
+        }
+
+        !pushed_out
+    }
+
+    // every block from the top of the buffer down, row by row
+    pub fn to_vec(&self) -> Vec<Option<TetroType>> {
+        self.blocks.clone()
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new(GAME_WIDTH, GAME_HEIGHT, GAME_BUFFER_HEIGHT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill_row(board: &mut Board, y: i32, hole: Option<i32>) {
+        for x in 0..board.width {
+            if Some(x) != hole { board.set(Cord(x, y), Some(TetroType::O)) }
+        }
+    }
+
+    #[test]
+    fn tetros_collide_with_the_walls_and_floor() {
+        let board = Board::new(10, 20, 2);
+
+        // the vertical I has its blocks in the third column of its shape
+        let vertical_i = |x, y| GameTetro::new(TetroType::I, Cord(x, y), 1);
+        assert!(!board.is_tetro_colliding(vertical_i(-2, 0)));
+        assert!(board.is_tetro_colliding(vertical_i(-3, 0)));
+        assert!(!board.is_tetro_colliding(vertical_i(7, 0)));
+        assert!(board.is_tetro_colliding(vertical_i(8, 0)));
+
+        assert!(!board.is_tetro_colliding(vertical_i(0, 16)));
+        assert!(board.is_tetro_colliding(vertical_i(0, 17)));
+
+        assert!(board.is_filled(Cord(-1, 0)));
+        assert!(board.is_filled(Cord(10, 0)));
+        assert!(board.is_filled(Cord(0, 20)));
+    }
+
+    #[test]
+    fn clearing_lines_with_gaps_between_them() {
+        let mut board = Board::new(10, 20, 2);
+
+        fill_row(&mut board, 19, None);
+        fill_row(&mut board, 18, Some(3));
+        fill_row(&mut board, 17, None);
+        fill_row(&mut board, 16, Some(5));
+        fill_row(&mut board, 15, None);
+
+        assert_eq!(board.clear_lines(), 3);
+
+        assert_eq!(board.row(19), board.full_row() & !(1 << 3));
+        assert_eq!(board.row(18), board.full_row() & !(1 << 5));
+        assert_eq!(board.get(Cord(0, 18)), Some(TetroType::O));
+        assert_eq!(board.get(Cord(5, 18)), None);
+        assert!((-2..18).all(|y| board.row(y) == 0));
+    }
+
+    #[test]
+    fn pushing_garbage_tops_out_when_blocks_leave_the_board() {
+        let mut board = Board::new(10, 4, 2);
+
+        fill_row(&mut board, 1, Some(0));
+        assert!(board.push_garbage(&[4, 4, 4]));
+        assert_eq!(board.row(-2), board.full_row() & !1);
+        assert_eq!(board.row(3), board.full_row() & !(1 << 4));
+
+        assert!(!board.push_garbage(&[4]));
+        assert!(board.is_garbage(Cord(0, 3)));
+    }
+}
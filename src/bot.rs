@@ -1,13 +1,10 @@
-use std::time::Instant;
-
 use rand::{thread_rng, Rng};
 
 use crate::{
-    GAME_WIDTH,
-    GAME_HEIGHT,
+    Cord,
     controls,
-    game::{self, Game},
-    tetros::{TetroType, GameTetro},
+    board::Board,
+    game::Game,
     serializer::show_debug_game
 };
 
@@ -39,6 +36,14 @@ impl Weigths {
     }
 }
 
+// the weights the bot plays with outside of training
+pub const TUNED_WEIGHTS: Weigths = Weigths {
+    holes_penalty: 16000,
+    bumpiness_penalty: 6,
+    height_penalty: 2,
+    line_clearing: [-90, -70, -50, 800]
+};
+
 #[derive(Clone, Copy, Debug)]
 pub struct Bot {
     pub weights: Weigths,
@@ -46,19 +51,19 @@ pub struct Bot {
 }
 
 impl Bot {
-    fn fitness_function(&self, blocks: [Option<TetroType>; (GAME_WIDTH * GAME_HEIGHT) as usize], lines_cleared: usize) -> i32 {
+    fn fitness_function(&self, blocks: &Board, lines_cleared: usize) -> i32 {
         let mut holes_amount = 0;
         let mut bumpiness = 0;
         let mut last_col_height = None;
         let mut height_penalty = 0;
 
-        for x in 0..GAME_WIDTH as usize {
+        for x in 0..blocks.width {
             let mut col_height = 0;
-            for y in 0..GAME_HEIGHT as i32 {
-                if blocks[x as usize + y as usize * GAME_WIDTH as usize].is_some() {
+            for y in -blocks.buffer_height..blocks.height {
+                if blocks.is_filled(Cord(x, y)) {
                     if col_height == 0 {
-                        col_height = GAME_HEIGHT as i32 - y;
-                        height_penalty += GAME_HEIGHT as i32 - y;
+                        col_height = blocks.height - y;
+                        height_penalty += blocks.height - y;
                     }
 
                 } else if col_height != 0 {
@@ -78,46 +83,47 @@ impl Bot {
         if lines_cleared != 0 { self.weights.line_clearing[lines_cleared - 1] } else { 0 }
     }
 
-    fn alternate_universe(&self, mut game: Game, depth: usize) -> (i32, (bool, i32, usize)) {
+    fn alternate_universe(&self, game: Game, depth: usize) -> (i32, (bool, i32, usize)) {
         let mut best_move: Option<(i32, (bool, i32, usize))> = None;
 
         for use_hold in 0..=1 {
             let use_hold = use_hold == 1;
+            if use_hold && !game.can_hold() { continue; };
 
             for rotation in 0..4usize {
-                for x in -2..GAME_WIDTH as i32 {
+                for x in -2..game.blocks.width {
                     let mut game = game.clone();
 
                     if use_hold { controls::hold_tetro(&mut game); };
 
-                    game.dropping_tetro.cord = game.dropping_tetro.tetro_type.start_pos();
+                    game.dropping_tetro.cord = game.blocks.start_pos(game.dropping_tetro.tetro_type);
                     game.dropping_tetro.cord.0 = x;
                     game.dropping_tetro.rotation = rotation;
 
-                    if game::is_tetro_colliding(game.blocks, game.dropping_tetro) { continue; };
+                    if game.blocks.is_tetro_colliding(game.dropping_tetro) { continue; };
 
-                    for _ in 0..GAME_HEIGHT as i32 {
+                    for _ in game.dropping_tetro.cord.1..game.blocks.height {
                         game.dropping_tetro.cord.1 += 1;
-                        if game::is_tetro_colliding(game.blocks, game.dropping_tetro) { break; };
+                        if game.blocks.is_tetro_colliding(game.dropping_tetro) { break; };
                     }
                     game.dropping_tetro.cord.1 -= 1;
 
-                    game::petrify_tetro(&mut game.blocks, game.dropping_tetro);
-                    let lines_cleared = game::clear_lines(&mut game.blocks);
+                    game.blocks.petrify_tetro(game.dropping_tetro);
+                    let lines_cleared = game.blocks.clear_lines();
 
                     game.next_tetro();
 
-                    let game_score = self.fitness_function(game.blocks, lines_cleared);
+                    let game_score = self.fitness_function(&game.blocks, lines_cleared);
                     if depth == 0 || !game.is_playing {
                         if best_move.is_none() || game_score > best_move.unwrap().0 {
-                            best_move = Some((game_score, (use_hold, x as i32, rotation)));
+                            best_move = Some((game_score, (use_hold, x, rotation)));
                         }
                     } else {
                         let universe_score = self.alternate_universe(game, depth - 1);
                         let game_score = universe_score.0 + game_score;
 
                         if best_move.is_none() || game_score > best_move.unwrap().0 {
-                            best_move = Some((game_score, (use_hold, x as i32, rotation)));
+                            best_move = Some((game_score, (use_hold, x, rotation)));
                         }
                     }
                 }
@@ -126,7 +132,7 @@ impl Bot {
 
         if best_move.is_none() {
             dbg!(depth);
-            dbg!(game.blocks, game.dropping_tetro);
+            dbg!(&game.blocks, game.dropping_tetro);
 
             show_debug_game(&game);
         }
@@ -152,6 +158,26 @@ impl Bot {
     }
 }
 
+// shifts and rotates the dropping tetro one step closer to a move from `Bot::best_move`,
+// returns true once it's in place and ready to be hard dropped
+pub fn move_towards(game: &mut Game, best_move: (bool, i32, usize)) -> bool {
+    if best_move.0 { controls::hold_tetro(game) };
+
+    let rotate_times = best_move.2 as i32 - game.dropping_tetro.rotation as i32;
+    let shift_amount = best_move.1 - game.dropping_tetro.cord.0;
+
+    if shift_amount == 0 && rotate_times == 0 { return true }
+
+    if shift_amount != 0 {
+        controls::shift_tetro(game, if shift_amount.is_positive() { 1 } else { -1 });
+    }
+    if rotate_times != 0 {
+        controls::rotate_tetro(game, rotate_times);
+    }
+
+    false
+}
+
 impl Default for Bot {
     fn default() -> Self {
         Self {
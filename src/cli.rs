@@ -0,0 +1,6 @@
+// looks up the value following a `--name` flag
+pub fn flag(args: &[String], name: &str) -> Option<String> {
+    let index = args.iter().position(|arg| arg == name)?;
+
+    args.get(index + 1).cloned()
+}
@@ -1,74 +1,87 @@
-use std::time::{Instant, Duration};
-use crate::{Cord, BLOCK_SIZE, GAME_POS, GAME_WIDTH, GAME_HEIGHT};
-use crate::game::{self, Game};
-use crate::tetros::GameTetro;
+use crate::srs;
+use crate::game::Game;
+
+// none of the controls do anything once the game is over
 
 pub fn shift_tetro(game: &mut Game, x_amount: i32) {
+    if !game.is_playing { return }
+
     let mut next = game.dropping_tetro;
     next.cord.0 += x_amount;
 
-    if !game::is_tetro_colliding(game.blocks, next) {
+    if !game.blocks.is_tetro_colliding(next) {
         game.dropping_tetro = next;
-
-        let mut lock_next = game.dropping_tetro;
-        lock_next.cord.1 += 1;
-
-        if game::is_tetro_colliding(game.blocks, lock_next) {
-            game.lock_delay = Instant::now();
-            game.last_drop_timing = Instant::now();
-        }
+        game.last_rotation_kick = None;
+        game.reset_lock_delay();
     }
 }
 
-pub fn rotate_tetro(game: &mut Game, rotate_times: i32) {
-    let mut next = game.dropping_tetro;
-    next.rotation = (next.rotation as i32 + rotate_times).rem_euclid(3 + 1) as usize; // calculate modulus of new rotation, NOT remainder which is %
+// returns the index of the kick that let the rotation through, if any did
+pub fn rotate_tetro(game: &mut Game, rotate_times: i32) -> Option<usize> {
+    if !game.is_playing { return None }
 
-    if !game::is_tetro_colliding(game.blocks, next) {
-        game.dropping_tetro = next;
+    let rotation = (game.dropping_tetro.rotation as i32 + rotate_times).rem_euclid(3 + 1) as usize; // calculate modulus of new rotation, NOT remainder which is %
+
+    let kicks = match (rotation + 4 - game.dropping_tetro.rotation) % 4 {
+        0 => return None,
+        1 => srs::kicks(game.dropping_tetro.tetro_type, game.dropping_tetro.rotation, true),
+        3 => srs::kicks(game.dropping_tetro.tetro_type, game.dropping_tetro.rotation, false),
+        _ => game.rules.kicks_180.kicks(game.dropping_tetro.rotation)
+    };
 
-        let mut lock_next = game.dropping_tetro;
-        lock_next.cord.1 += 1;
+    for (kick_index, kick) in kicks.iter().enumerate() {
+        let mut next = game.dropping_tetro;
+        next.rotation = rotation;
+        next.cord.0 += kick.0;
+        next.cord.1 += kick.1;
 
-        if game::is_tetro_colliding(game.blocks, lock_next) {
-            game.lock_delay = Instant::now();
-            game.last_drop_timing = Instant::now();
-        }
+        if game.blocks.is_tetro_colliding(next) { continue }
+
+        game.dropping_tetro = next;
+        game.last_rotation_kick = Some(kick_index);
+        game.last_rotation_was_180 = rotate_times.rem_euclid(4) == 2;
+        game.reset_lock_delay();
+
+        return Some(kick_index);
     }
+
+    None
 }
 
 pub fn hard_drop(game: &mut Game) {
+    if !game.is_playing { return }
+
     let mut next = game.dropping_tetro;
 
     let mut cells_dropped = 0;
-    for _ in game.dropping_tetro.cord.1..GAME_HEIGHT as i32 {
+    for _ in game.dropping_tetro.cord.1..game.blocks.height {
         next.cord.1 += 1;
-        if game::is_tetro_colliding(game.blocks, next) { break; };
+        if game.blocks.is_tetro_colliding(next) { break; };
 
         cells_dropped += 1;
     }
     next.cord.1 -= 1;
 
-    game.score += cells_dropped * 2;
+    game.score += game.rules.scoring.hard_drop(cells_dropped);
 
-    game::petrify_tetro(&mut game.blocks, next);
-    let lines_cleared = game::clear_lines(&mut game.blocks);
-    game.add_lines_cleared(lines_cleared);
+    game.dropping_tetro = next;
+    if cells_dropped > 0 { game.last_rotation_kick = None; };
 
-    game.next_tetro();
+    game.lock_tetro();
 }
 
 pub fn hold_tetro(game: &mut Game) {
+    if !game.can_hold() { return }
+
     let hold_tetro = game.hold_tetro;
 
     game.hold_tetro = Some(game.dropping_tetro.tetro_type);
 
     if let Some(hold_tetro) = hold_tetro {
-        game.dropping_tetro = GameTetro::new(hold_tetro, hold_tetro.start_pos(), 0);
-        game.last_drop_timing = Instant::now();
+        game.spawn_tetro(hold_tetro);
     } else {
         game.next_tetro();
     }
 
+    game.has_held = true;
 }
-
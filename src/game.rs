@@ -1,180 +1,690 @@
-use std::time::{Instant, Duration};
+use std::mem;
+use std::time::Duration;
 
-use crate::{Cord, TETRO_TYPES_AMOUNT, GAME_WIDTH, GAME_HEIGHT};
+use rand::{thread_rng, Rng};
+
+use crate::{Cord, TETRO_TYPES_AMOUNT};
+use crate::board::Board;
 use crate::tetros::{GameTetro, TetroType};
+use crate::randomizer::{Randomizer, Bag};
+use crate::rules::{Rules, LockDown};
+use crate::gravity;
+use crate::mode::{Mode, SPLIT_LINES};
+use crate::garbage::{self, GarbageHoles, PendingGarbage};
+
+pub const LINES_PER_LEVEL: i32 = 10;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GameOverReason {
+    // a new tetro spawned overlapping the stack
+    BlockOut,
+    // a tetro locked completely above the visible playfield
+    LockOut,
+    // a tetro locked partly above the visible playfield, only with `Rules::partial_lock_out`
+    PartialLockOut,
+    // garbage pushed blocks off the top of the buffer
+    TopOut,
+    // the goal of the mode was reached or its time ran out
+    Completed,
+    // the opponent topped out first
+    Won,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TSpin {
+    None,
+    Mini,
+    Full
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct LockResult {
+    pub tetro: GameTetro,
+    pub lines_cleared: usize,
+    pub t_spin: TSpin,
+    pub perfect_clear: bool,
+    pub back_to_back: bool,
+    pub combo: i32,
+    pub score: i32,
+    pub attack: i32
+}
+
+impl LockResult {
+    // tetrises and spins that clear lines keep the back-to-back chain going
+    pub fn is_difficult(&self) -> bool {
+        self.lines_cleared == 4 || (self.t_spin != TSpin::None && self.lines_cleared > 0)
+    }
+}
+
+// everything a placement changes, kept around in practice mode so placements can be undone
+#[derive(Clone)]
+pub struct Snapshot {
+    pub randomizer: Box<dyn Randomizer>,
+    pub tetros_dealt: usize,
+    pub lines: i32,
+    pub level: i32,
+    pub score: i32,
+    pub attack: i32,
+    pub pieces: u32,
+    pub splits: Vec<Duration>,
+    pub garbage_lines: i32,
+    pub garbage_holes: GarbageHoles,
+    pub pending_garbage: PendingGarbage,
+    pub combo: i32,
+    pub back_to_back: bool,
+    pub tetro_queue: Vec<TetroType>,
+    pub dropping_tetro: TetroType,
+    pub hold_tetro: Option<TetroType>,
+    pub has_held: bool,
+    pub blocks: Board,
+    pub last_lock: Option<LockResult>,
+    pub game_over_reason: Option<GameOverReason>
+}
 
 #[derive(Clone)]
 pub struct Game {
+    pub rules: Rules,
+    pub randomizer: Box<dyn Randomizer>,
+    // tetros taken from the randomizer so far, so saved games can pick up where it left off
+    pub tetros_dealt: usize,
     pub lines: i32,
+    pub level: i32,
     pub score: i32,
+    // garbage lines sent in total, before cancelling any pending garbage
+    pub attack: i32,
+    // garbage lines left to send after cancelling, for whoever runs the match to pass on
+    pub outgoing_garbage: i32,
+    // tetros locked so far
+    pub pieces: u32,
+    // the time at which every further `SPLIT_LINES` lines were cleared
+    pub splits: Vec<Duration>,
+    // garbage rows still in the stack, these always make up the bottom of the board
+    pub garbage_lines: i32,
+    pub garbage_holes: GarbageHoles,
+    pub pending_garbage: PendingGarbage,
+    pub combo: i32,
+    pub back_to_back: bool,
     pub tetro_queue: Vec<TetroType>,
     pub dropping_tetro: GameTetro,
     pub hold_tetro: Option<TetroType>,
-    pub blocks: [Option<TetroType>; (GAME_WIDTH * GAME_HEIGHT) as usize],
+    pub has_held: bool,
+    pub blocks: Board,
     pub is_soft_dropping: bool,
-    pub last_drop_timing: Instant,
-    pub lock_delay: Instant,
-    pub is_playing: bool
+    pub last_rotation_kick: Option<usize>,
+    // the 180 kick table has nothing to do with the SRS kick that upgrades a mini
+    pub last_rotation_was_180: bool,
+    pub last_lock: Option<LockResult>,
+    pub time: Duration,
+    pub last_drop_timing: Duration,
+    pub lock_delay: Duration,
+    pub lock_resets: u32,
+    pub lowest_row: i32,
+    pub is_playing: bool,
+    pub game_over_reason: Option<GameOverReason>,
+    pub undo_history: Vec<Snapshot>,
+    pub redo_history: Vec<Snapshot>
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Game {
     pub fn new() -> Self {
-        let mut tetro_queue = TetroType::random_set();
-        tetro_queue.append(&mut TetroType::random_set());
+        Self::with_seed(thread_rng().gen())
+    }
+
+    // games built with the same seed receive exactly the same pieces
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_randomizer(Box::new(Bag::seven(seed)))
+    }
+
+    pub fn with_randomizer(randomizer: Box<dyn Randomizer>) -> Self {
+        Self::with_rules(randomizer, Rules::default())
+    }
+
+    pub fn with_rules(mut randomizer: Box<dyn Randomizer>, rules: Rules) -> Self {
+        let mut tetro_queue = vec![];
+        let mut tetros_dealt = fill_queue(&mut tetro_queue, randomizer.as_mut());
         let dropping_tetro_type = tetro_queue.pop().unwrap();
+        tetros_dealt += fill_queue(&mut tetro_queue, randomizer.as_mut());
+
+        let mut blocks = Board::new(rules.board_width, rules.board_height, rules.buffer_height);
+
+        let garbage_lines = match rules.mode {
+            Mode::Dig { rows, messiness } => garbage::fill_bottom(&mut blocks, rows, &mut GarbageHoles::new(randomizer.seed() ^ garbage::DIG_SEED_SALT, messiness)),
+            _ => 0
+        };
 
-        Self {
+        let garbage_holes = GarbageHoles::new(randomizer.seed() ^ garbage::ATTACK_SEED_SALT, rules.garbage_messiness);
+        let level = rules.start_level;
+
+        let mut game = Self {
+            rules,
+            randomizer,
+            tetros_dealt,
             lines: 0,
+            level,
             score: 0,
-            dropping_tetro: GameTetro::new(dropping_tetro_type, dropping_tetro_type.start_pos(), 0),
+            attack: 0,
+            outgoing_garbage: 0,
+            pieces: 0,
+            splits: vec![],
+            garbage_lines,
+            garbage_holes,
+            pending_garbage: PendingGarbage::default(),
+            combo: -1,
+            back_to_back: false,
+            dropping_tetro: GameTetro::new(dropping_tetro_type, blocks.start_pos(dropping_tetro_type), 0),
+            lowest_row: blocks.start_pos(dropping_tetro_type).1,
             tetro_queue,
             hold_tetro: None,
-            blocks: [None; (GAME_WIDTH * GAME_HEIGHT) as usize],
+            has_held: false,
+            blocks,
             is_soft_dropping: false,
-            last_drop_timing: Instant::now(),
-            lock_delay: Instant::now(),
-            is_playing: true
+            last_rotation_kick: None,
+            last_rotation_was_180: false,
+            last_lock: None,
+            time: Duration::ZERO,
+            last_drop_timing: Duration::ZERO,
+            lock_delay: Duration::ZERO,
+            lock_resets: 0,
+            is_playing: true,
+            game_over_reason: None,
+            undo_history: vec![],
+            redo_history: vec![]
+        };
+
+        game.spawn_tetro(dropping_tetro_type);
+
+        game
+    }
+
+    // advances the game clock by `delta` instead of reading wall time, so the
+    // same inputs and deltas always play out the same way
+    pub fn next_frame(&mut self, delta: Duration) {
+        // a finished game stops changing, including its clock
+        if !self.is_playing { return }
+
+        self.time += delta;
+
+        if let Some(time_limit) = self.rules.mode.time_limit() {
+            self.time = self.time.min(time_limit);
+        }
+        if self.rules.mode.is_completed(self) {
+            self.end_game(GameOverReason::Completed);
+
+            return;
+        }
+
+        let gravity = self.gravity();
+
+        if gravity >= gravity::MAX_GRAVITY {
+            while self.drop_tetro() {}
+        } else if gravity > 0. {
+            let drop_interval = Duration::from_secs_f64(1. / (gravity * gravity::FRAMES_PER_SECOND));
+
+            // carry over at most one drop from before this frame, otherwise a faster interval
+            // (like starting a soft drop) would replay all the time since the last drop at once
+            self.last_drop_timing = self.last_drop_timing.max(self.time.saturating_sub(drop_interval + delta));
+
+            while self.elapsed(self.last_drop_timing) > drop_interval {
+                if !self.drop_tetro() { break }
+                self.last_drop_timing += drop_interval;
+            }
+        } else {
+            self.last_drop_timing = self.time;
+        }
+
+        // practice mode has no lock delay, tetros only lock when hard dropped
+        if self.rules.mode.is_practice() { return }
+
+        let mut next = self.dropping_tetro;
+        next.cord.1 += 1;
+
+        if self.blocks.is_tetro_colliding(next) {
+            self.last_drop_timing = self.time;
+
+            if self.elapsed(self.lock_delay) > Duration::from_millis(500) || self.is_out_of_lock_resets() {
+                self.lock_tetro();
+
+                self.last_drop_timing = self.time;
+                self.lock_delay = self.time;
+            }
         }
     }
 
-    pub fn next_frame(&mut self) {
-        let drop_delta = self.last_drop_timing.elapsed();
+    pub fn gravity(&self) -> f64 {
+        let gravity = if self.rules.mode.is_practice() { 0. } else { self.rules.gravity.cells_per_frame(self.level) };
+
+        if self.is_soft_dropping { gravity.max(gravity::SOFT_DROP_GRAVITY) } else { gravity }
+    }
 
+    fn drop_tetro(&mut self) -> bool {
         let mut next = self.dropping_tetro;
         next.cord.1 += 1;
 
-        if is_tetro_colliding(self.blocks, next) {
-            if self.lock_delay.elapsed() > Duration::from_millis(500) {
-                petrify_tetro(&mut self.blocks, self.dropping_tetro);
+        if self.blocks.is_tetro_colliding(next) { return false }
 
-                let lines_cleared = clear_lines(&mut self.blocks);
-                self.add_lines_cleared(lines_cleared);
+        self.dropping_tetro = next;
+        self.last_rotation_kick = None;
+        self.reset_lock_delay();
 
-                self.next_tetro();
+        if self.is_soft_dropping { self.score += self.rules.scoring.soft_drop(1); };
 
-                self.last_drop_timing = Instant::now();
-                self.lock_delay = Instant::now();
-            }
+        true
+    }
+
+    // called after every successful shift, rotation or drop of the dropping tetro
+    pub fn reset_lock_delay(&mut self) {
+        if self.dropping_tetro.cord.1 > self.lowest_row {
+            self.lowest_row = self.dropping_tetro.cord.1;
+            self.lock_resets = 0;
+            self.lock_delay = self.time;
+
+            return;
+        }
+
+        let mut below = self.dropping_tetro;
+        below.cord.1 += 1;
+
+        if !self.blocks.is_tetro_colliding(below) { return }
+
+        match self.rules.lock_down {
+            LockDown::Infinite => self.lock_delay = self.time,
+            LockDown::ExtendedPlacement(max_resets) => {
+                if self.lock_resets < max_resets {
+                    self.lock_resets += 1;
+                    self.lock_delay = self.time;
+                }
+            },
+            LockDown::StepReset => ()
+        }
+    }
+
+    pub fn is_out_of_lock_resets(&self) -> bool {
+        match self.rules.lock_down {
+            LockDown::ExtendedPlacement(max_resets) => self.lock_resets >= max_resets,
+            _ => false
+        }
+    }
+
+    // returns None when the game is already over
+    pub fn lock_tetro(&mut self) -> Option<LockResult> {
+        if !self.is_playing { return None }
+
+        if self.rules.mode.is_practice() {
+            self.undo_history.push(self.snapshot());
+            self.redo_history.clear();
+        }
+
+        let tetro = self.dropping_tetro;
+        let t_spin = detect_t_spin(&self.blocks, tetro, self.last_rotation_kick, self.last_rotation_was_180);
+
+        self.blocks.petrify_tetro(tetro);
+        self.garbage_lines -= self.full_garbage_rows();
+        let lines_cleared = self.blocks.clear_lines();
+
+        let mut lock = LockResult {
+            tetro,
+            lines_cleared,
+            t_spin,
+            perfect_clear: lines_cleared > 0 && self.blocks.is_empty(),
+            back_to_back: false,
+            combo: -1,
+            score: 0,
+            attack: 0
+        };
+
+        self.add_lines_cleared(&mut lock);
+        self.last_lock = Some(lock);
+        self.has_held = false;
+        self.pieces += 1;
+
+        let cells_above = tetro.cells().iter().filter(|cord| cord.is_in_buffer()).count();
+
+        if cells_above == 4 {
+            self.end_game(GameOverReason::LockOut);
+        } else if cells_above > 0 && self.rules.partial_lock_out {
+            self.end_game(GameOverReason::PartialLockOut);
+        } else if self.rules.mode.is_completed(self) {
+            self.end_game(GameOverReason::Completed);
         } else {
-            if drop_delta > Duration::from_millis(if self.is_soft_dropping { 50 } else { 750 }) {
-                self.dropping_tetro = next;
+            // garbage only rises when a lock doesn't clear any lines
+            if lines_cleared == 0 { self.push_pending_garbage(); }
 
-                if self.is_soft_dropping { self.score += 1; };
+            if self.is_playing { self.next_tetro(); }
+        }
 
-                self.last_drop_timing = Instant::now();
-                self.lock_delay = Instant::now();
-            }
+        Some(lock)
+    }
+
+    fn full_garbage_rows(&self) -> i32 {
+        (self.blocks.height - self.garbage_lines..self.blocks.height)
+            .filter(|y| self.blocks.row(*y) == self.blocks.full_row())
+            .count() as i32
+    }
+
+    pub fn push_pending_garbage(&mut self) {
+        for lines in self.pending_garbage.take() {
+            self.push_garbage(lines);
         }
     }
 
+    // pushes one attack worth of garbage rows into the bottom of the stack, meant to be called
+    // between tetros since it doesn't move the dropping tetro along with the stack
+    pub fn push_garbage(&mut self, lines: i32) {
+        if !self.is_playing { return }
+
+        let holes = self.garbage_holes.attack_holes(lines, self.blocks.width);
+        let fits = self.blocks.push_garbage(&holes);
+
+        self.garbage_lines = i32::min(self.garbage_lines + holes.len() as i32, self.blocks.rows());
+
+        if !fits { self.end_game(GameOverReason::TopOut); }
+    }
+
     pub fn next_tetro(&mut self) {
         let tetro_type = self.tetro_queue.pop().unwrap();
+        self.tetros_dealt += fill_queue(&mut self.tetro_queue, self.randomizer.as_mut());
+
+        self.spawn_tetro(tetro_type);
+    }
+
+    pub fn spawn_tetro(&mut self, tetro_type: TetroType) {
+        let mut next = GameTetro::new(tetro_type, self.blocks.start_pos(tetro_type), 0);
+
+        if self.blocks.is_tetro_colliding(next) {
+            self.dropping_tetro = next;
+            self.end_game(GameOverReason::BlockOut);
 
-        if self.tetro_queue.len() < TETRO_TYPES_AMOUNT {
-            self.tetro_queue.append(&mut TetroType::random_set());
+            return;
         }
 
-        let next = GameTetro::new(tetro_type, tetro_type.start_pos(), 0);
+        // drop into view right away when there's room for it
+        let mut below = next;
+        below.cord.1 += 1;
+        if !self.blocks.is_tetro_colliding(below) { next = below };
 
         self.dropping_tetro = next;
-        self.last_drop_timing = Instant::now();
+        self.last_rotation_kick = None;
+        self.last_drop_timing = self.time;
+        self.lock_delay = self.time;
+        self.lock_resets = 0;
+        self.lowest_row = next.cord.1;
+    }
 
-        if is_tetro_colliding(self.blocks, next) {
-            self.is_playing = false;
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            randomizer: self.randomizer.clone(),
+            tetros_dealt: self.tetros_dealt,
+            lines: self.lines,
+            level: self.level,
+            score: self.score,
+            attack: self.attack,
+            pieces: self.pieces,
+            splits: self.splits.clone(),
+            garbage_lines: self.garbage_lines,
+            garbage_holes: self.garbage_holes.clone(),
+            pending_garbage: self.pending_garbage.clone(),
+            combo: self.combo,
+            back_to_back: self.back_to_back,
+            tetro_queue: self.tetro_queue.clone(),
+            dropping_tetro: self.dropping_tetro.tetro_type,
+            hold_tetro: self.hold_tetro,
+            has_held: self.has_held,
+            blocks: self.blocks.clone(),
+            last_lock: self.last_lock,
+            game_over_reason: self.game_over_reason
         }
     }
 
+    // the dropping tetro starts over from the top
+    pub fn restore(&mut self, snapshot: Snapshot) {
+        self.randomizer = snapshot.randomizer;
+        self.tetros_dealt = snapshot.tetros_dealt;
+        self.lines = snapshot.lines;
+        self.level = snapshot.level;
+        self.score = snapshot.score;
+        self.attack = snapshot.attack;
+        self.pieces = snapshot.pieces;
+        self.splits = snapshot.splits;
+        self.garbage_lines = snapshot.garbage_lines;
+        self.garbage_holes = snapshot.garbage_holes;
+        self.pending_garbage = snapshot.pending_garbage;
+        self.combo = snapshot.combo;
+        self.back_to_back = snapshot.back_to_back;
+        self.tetro_queue = snapshot.tetro_queue;
+        self.hold_tetro = snapshot.hold_tetro;
+        self.has_held = snapshot.has_held;
+        self.blocks = snapshot.blocks;
+        self.last_lock = snapshot.last_lock;
+        self.is_playing = snapshot.game_over_reason.is_none();
+        self.game_over_reason = snapshot.game_over_reason;
+
+        self.spawn_tetro(snapshot.dropping_tetro);
+    }
+
+    // takes back the last placement, returns whether there was one
+    pub fn undo(&mut self) -> bool {
+        let Some(snapshot) = self.undo_history.pop() else { return false };
+
+        self.redo_history.push(self.snapshot());
+        self.restore(snapshot);
+
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let Some(snapshot) = self.redo_history.pop() else { return false };
+
+        self.undo_history.push(self.snapshot());
+        self.restore(snapshot);
+
+        true
+    }
+
+    // replaces the upcoming tetro, only allowed in practice mode
+    pub fn choose_next_tetro(&mut self, tetro_type: TetroType) {
+        if !self.rules.mode.is_practice() || !self.is_playing { return }
+
+        *self.tetro_queue.last_mut().unwrap() = tetro_type;
+    }
+
+    pub fn end_game(&mut self, reason: GameOverReason) {
+        self.is_playing = false;
+        self.game_over_reason = Some(reason);
+    }
+
+    pub fn can_hold(&self) -> bool {
+        self.is_playing && self.rules.hold_enabled && !self.has_held
+    }
+
+    pub fn elapsed(&self, since: Duration) -> Duration {
+        self.time.saturating_sub(since)
+    }
+
     pub fn get_next_tetro(&self) -> TetroType {
         *self.tetro_queue.last().unwrap()
     }
 
-    pub fn add_lines_cleared(&mut self, lines_cleared: usize) {
-        let score_multiplier = match lines_cleared {
-            1 => 100,
-            2 => 300,
-            3 => 500,
-            4 => 800,
-            _ => 0
-        };
-
-        self.score += (self.lines / 10 + 1) * score_multiplier;
+    pub fn attack_per_minute(&self) -> f64 {
+        if self.time.is_zero() { return 0. }
 
-        self.lines += lines_cleared as i32;
+        self.attack as f64 / (self.time.as_secs_f64() / 60.)
     }
-}
 
-pub fn petrify_tetro(blocks: &mut [Option<TetroType>; (GAME_WIDTH * GAME_HEIGHT) as usize], tetro: GameTetro) {
-    let shape = tetro.tetro_type.shape(tetro.rotation);
-    let shape_size = tetro.tetro_type.shape_size();
+    pub fn take_outgoing_garbage(&mut self) -> i32 {
+        mem::take(&mut self.outgoing_garbage)
+    }
 
-    for i in 0..shape_size * shape_size {
-        let bit = shape >> i & 1;
-        if bit == 0 { continue }
+    pub fn pieces_per_second(&self) -> f64 {
+        if self.time.is_zero() { return 0. }
 
-        let relative_cord = Cord(i as i32 % shape_size, i as i32 / shape_size);
-        let cord = Cord(tetro.cord.0 + relative_cord.0, tetro.cord.1 + relative_cord.1);
+        self.pieces as f64 / self.time.as_secs_f64()
+    }
 
-        if (cord.0 + cord.1 * GAME_WIDTH) as usize > 201 {
-            dbg!(cord);
-            dbg!(tetro);
-            dbg!("petrifying tetro outside");
+    pub fn add_lines_cleared(&mut self, lock: &mut LockResult) {
+        if lock.lines_cleared > 0 {
+            self.combo += 1;
+            lock.back_to_back = lock.is_difficult() && self.back_to_back;
+            self.back_to_back = lock.is_difficult();
+        } else {
+            self.combo = -1;
         }
+        lock.combo = self.combo;
 
-        blocks[(cord.0 + cord.1 * GAME_WIDTH) as usize] = Some(tetro.tetro_type);
+        lock.score = self.rules.scoring.line_clear(lock, self.level);
+        self.score += lock.score;
+
+        lock.attack = self.rules.attack_table.attack(lock);
+        self.attack += lock.attack;
+        self.outgoing_garbage += self.pending_garbage.cancel(lock.attack);
+
+        self.lines += lock.lines_cleared as i32;
+        self.level = self.rules.start_level + self.lines / LINES_PER_LEVEL;
+
+        while (self.splits.len() as i32 + 1) * SPLIT_LINES <= self.lines {
+            self.splits.push(self.time);
+        }
     }
 }
 
-pub fn is_tetro_colliding(blocks: [Option<TetroType>; (GAME_WIDTH * GAME_HEIGHT) as usize], tetro: GameTetro) -> bool {
-    if tetro.cord.is_outside_game(tetro.tetro_type, tetro.rotation) { return true }
+// 3-corner rule: a T locked right after a rotation with three of the corners around its
+// center filled is a spin, and a mini unless both corners in front of its nub are filled
+// or the rotation was a quarter turn that needed the last SRS kick
+pub fn detect_t_spin(blocks: &Board, tetro: GameTetro, last_rotation_kick: Option<usize>, last_rotation_was_180: bool) -> TSpin {
+    if tetro.tetro_type != TetroType::T { return TSpin::None }
 
-    let shape = tetro.tetro_type.shape(tetro.rotation);
-    let shape_size = tetro.tetro_type.shape_size();
+    let Some(kick_index) = last_rotation_kick else { return TSpin::None };
 
-    for i in 0..shape_size * shape_size {
-        let bit = shape >> i & 1;
-        if bit == 0 { continue }
+    let corners = [Cord(0, 0), Cord(2, 0), Cord(2, 2), Cord(0, 2)];
+    let is_filled = |corner: Cord| blocks.is_filled(Cord(tetro.cord.0 + corner.0, tetro.cord.1 + corner.1));
 
-        let relative_cord = Cord(i as i32 % shape_size, i as i32 / shape_size);
-        let cord = Cord(tetro.cord.0 + relative_cord.0, tetro.cord.1 + relative_cord.1);
+    let filled_corners = corners.iter().filter(|corner| is_filled(**corner)).count();
+    if filled_corners < 3 { return TSpin::None }
 
-        if (cord.0 + cord.1 * GAME_WIDTH) as usize > 201 {
-            dbg!(cord);
-            dbg!(tetro);
-            dbg!("collision detection outside");
-        }
+    // the corners are listed clockwise, so the two in front of the nub start at the rotation
+    let front_filled = is_filled(corners[tetro.rotation]) && is_filled(corners[(tetro.rotation + 1) % 4]);
 
-        let block = blocks[(cord.0 + cord.1 * GAME_WIDTH) as usize];
+    if front_filled || (kick_index == 4 && !last_rotation_was_180) {
+        TSpin::Full
+    } else {
+        TSpin::Mini
+    }
+}
+
+// the queue is popped from the back, so new tetros are dealt in at the front, returns how many were dealt
+fn fill_queue(tetro_queue: &mut Vec<TetroType>, randomizer: &mut dyn Randomizer) -> usize {
+    let mut dealt = 0;
 
-        if block.is_some() { return true; };
+    while tetro_queue.len() < TETRO_TYPES_AMOUNT {
+        tetro_queue.insert(0, randomizer.next_tetro());
+        dealt += 1;
     }
 
-    false
+    dealt
 }
 
-pub fn clear_lines(blocks: &mut [Option<TetroType>; (GAME_WIDTH * GAME_HEIGHT) as usize]) -> usize {
-    let mut lines_to_remove = [false; GAME_HEIGHT as usize];
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controls;
 
-    for y in (0..GAME_HEIGHT as usize).rev() {
-        let row_is_full = (0..GAME_WIDTH as usize).all(|x| {
-            blocks[x + y * GAME_WIDTH as usize].is_some()
-        });
-        
-        if row_is_full {
-            lines_to_remove[y] = true;
+    fn fill(board: &mut Board, cords: &[Cord]) {
+        for cord in cords {
+            board.set(*cord, Some(TetroType::O));
         }
     }
 
-    let mut lines_removed = 0;
-    for y in (0..GAME_HEIGHT as usize).rev() {
-        if lines_to_remove[y] {
-            lines_removed += 1;
-        } else if lines_removed != 0 {
-            for x in 0..GAME_WIDTH as usize {
-                blocks[x + (y + lines_removed) * GAME_WIDTH as usize] = blocks[x + y * GAME_WIDTH as usize];
-            }
+    fn fill_row(board: &mut Board, y: i32, holes: &[i32]) {
+        for x in 0..board.width {
+            if !holes.contains(&x) { board.set(Cord(x, y), Some(TetroType::O)) }
         }
     }
 
-    lines_removed
-}
+    // a T pointing up in a one wide gap on the floor, with the corners below it filled by the
+    // floor and one corner above it filled by an overhang
+    fn mini_slot() -> (Board, GameTetro) {
+        let mut board = Board::default();
+        fill(&mut board, &[Cord(3, 18)]);
+
+        (board, GameTetro::new(TetroType::T, Cord(3, 18), 0))
+    }
+
+    #[test]
+    fn t_spin_double() {
+        let mut game = Game::with_seed(0);
+        fill_row(&mut game.blocks, 19, &[4]);
+        fill_row(&mut game.blocks, 18, &[3, 4, 5]);
+        fill(&mut game.blocks, &[Cord(3, 17)]);
+
+        game.dropping_tetro = GameTetro::new(TetroType::T, Cord(3, 17), 2);
+        game.last_rotation_kick = Some(0);
+
+        let lock = game.lock_tetro().unwrap();
+        assert_eq!(lock.t_spin, TSpin::Full);
+        assert_eq!(lock.lines_cleared, 2);
+        assert_eq!(lock.score, 1200);
+        assert_eq!(lock.attack, 4);
+    }
+
+    #[test]
+    fn t_spin_needs_a_rotation_and_three_corners() {
+        let (board, tetro) = mini_slot();
 
+        assert_eq!(detect_t_spin(&board, tetro, None, false), TSpin::None);
+        assert_eq!(detect_t_spin(&Board::default(), GameTetro::new(TetroType::T, Cord(3, 10), 0), Some(0), false), TSpin::None);
+        assert_eq!(detect_t_spin(&board, GameTetro::new(TetroType::L, Cord(3, 18), 0), Some(0), false), TSpin::None);
+    }
+
+    #[test]
+    fn mini_t_spin() {
+        let (board, tetro) = mini_slot();
+
+        assert_eq!(detect_t_spin(&board, tetro, Some(0), false), TSpin::Mini);
+    }
+
+    #[test]
+    fn last_srs_kick_upgrades_a_mini() {
+        let (board, tetro) = mini_slot();
+
+        assert_eq!(detect_t_spin(&board, tetro, Some(4), false), TSpin::Full);
+    }
+
+    #[test]
+    fn last_180_kick_does_not_upgrade_a_mini() {
+        let (board, tetro) = mini_slot();
+
+        assert_eq!(detect_t_spin(&board, tetro, Some(4), true), TSpin::Mini);
+    }
+
+    #[test]
+    fn rotating_records_whether_it_was_a_180() {
+        let mut game = Game::with_seed(0);
+        game.dropping_tetro = GameTetro::new(TetroType::T, Cord(3, 5), 0);
+
+        assert_eq!(controls::rotate_tetro(&mut game, 2), Some(0));
+        assert!(game.last_rotation_was_180);
+
+        assert_eq!(controls::rotate_tetro(&mut game, 1), Some(0));
+        assert!(!game.last_rotation_was_180);
+    }
+
+    #[test]
+    fn finished_games_stop_changing() {
+        let mut game = Game::with_seed(0);
+        game.end_game(GameOverReason::BlockOut);
+
+        for _ in 0..200 {
+            game.next_frame(Duration::from_millis(16));
+        }
+        assert!(game.lock_tetro().is_none());
+
+        assert_eq!(game.pieces, 0);
+        assert_eq!(game.time, Duration::ZERO);
+        assert_eq!(game.game_over_reason, Some(GameOverReason::BlockOut));
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::VecDeque;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::Cord;
+use crate::board::Board;
+
+// mixed into the game seed so the garbage holes don't follow the same random stream as the
+// randomizer dealing the tetros, or the dig rows the same one as incoming garbage
+pub const DIG_SEED_SALT: u64 = 0x9e37_79b9_7f4a_7c15;
+pub const ATTACK_SEED_SALT: u64 = 0xc2b2_ae3d_27d4_eb4f;
+
+// picks the hole column of each garbage row, moving the hole to another column with a chance
+// of `messiness`, so 0 lines every hole up into one well (clean garbage) and 1 moves it on
+// every row (messy garbage)
+#[derive(Clone, Debug)]
+pub struct GarbageHoles {
+    rng: StdRng,
+    messiness: f64,
+    hole: Option<i32>
+}
+
+impl GarbageHoles {
+    pub fn new(seed: u64, messiness: f64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            messiness: messiness.clamp(0., 1.),
+            hole: None
+        }
+    }
+
+    pub fn next_hole(&mut self, width: i32) -> i32 {
+        let hole = match self.hole {
+            Some(hole) if width > 1 && self.rng.gen_bool(self.messiness) => (hole + self.rng.gen_range(1..width)) % width,
+            Some(hole) => hole,
+            None => self.rng.gen_range(0..width)
+        };
+
+        self.hole = Some(hole);
+
+        hole
+    }
+
+    // the holes for the rows of one attack from the bottom up, every attack starts out in
+    // another column than the last one so separate attacks never line up into one well
+    pub fn attack_holes(&mut self, lines: i32, width: i32) -> Vec<i32> {
+        if lines <= 0 { return vec![] }
+
+        let hole = match self.hole {
+            Some(hole) if width > 1 => (hole + self.rng.gen_range(1..width)) % width,
+            _ => self.rng.gen_range(0..width)
+        };
+        self.hole = Some(hole);
+
+        let mut holes = vec![hole];
+        holes.extend((1..lines).map(|_| self.next_hole(width)));
+
+        holes
+    }
+}
+
+// incoming attacks waiting to be pushed into the stack, oldest first
+#[derive(Clone, Debug, Default)]
+pub struct PendingGarbage {
+    attacks: VecDeque<i32>
+}
+
+impl PendingGarbage {
+    pub fn receive(&mut self, lines: i32) {
+        if lines > 0 { self.attacks.push_back(lines) }
+    }
+
+    // outgoing attack cancels the oldest pending garbage first, returns what's left of it to send
+    pub fn cancel(&mut self, mut attack: i32) -> i32 {
+        while attack > 0 {
+            let Some(lines) = self.attacks.front_mut() else { break };
+
+            let cancelled = i32::min(attack, *lines);
+            *lines -= cancelled;
+            attack -= cancelled;
+
+            if *lines == 0 { self.attacks.pop_front(); }
+        }
+
+        attack
+    }
+
+    pub fn lines(&self) -> i32 {
+        self.attacks.iter().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attacks.is_empty()
+    }
+
+    pub fn take(&mut self) -> Vec<i32> {
+        self.attacks.drain(..).collect()
+    }
+}
+
+// fills the bottom `rows` rows of an empty board with garbage, one hole per row, and
+// returns how many rows fit
+pub fn fill_bottom(blocks: &mut Board, rows: i32, holes: &mut GarbageHoles) -> i32 {
+    let rows = rows.clamp(0, blocks.height);
+
+    for y in (blocks.height - rows..blocks.height).rev() {
+        let hole = holes.next_hole(blocks.width);
+
+        for x in (0..blocks.width).filter(|x| *x != hole) {
+            blocks.set_garbage(Cord(x, y));
+        }
+    }
+
+    rows
+}
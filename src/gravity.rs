@@ -0,0 +1,48 @@
+pub const FRAMES_PER_SECOND: f64 = 60.;
+
+// gravity is measured in G, cells dropped per frame, and 20G drops straight to the floor
+pub const MAX_GRAVITY: f64 = 20.;
+pub const SOFT_DROP_GRAVITY: f64 = 1. / 3.;
+
+const NES_FRAMES_PER_CELL: [i32; 30] = [
+    48, 43, 38, 33, 28, 23, 18, 13, 8, 6,
+    5, 5, 5, 4, 4, 4, 3, 3, 3, 2,
+    2, 2, 2, 2, 2, 2, 2, 2, 2, 1,
+];
+
+#[derive(Clone, Debug, Default)]
+pub enum Gravity {
+    #[default]
+    Guideline,
+    Nes,
+    // G for each level starting at level 1, the last entry is used for every level after it
+    Table(Vec<f64>),
+    Fixed(f64),
+}
+
+impl Gravity {
+    pub fn cells_per_frame(&self, level: i32) -> f64 {
+        let level = level.max(1);
+
+        let gravity = match self {
+            Gravity::Guideline => {
+                let seconds_per_cell = (0.8 - (level - 1) as f64 * 0.007).powi(level - 1);
+
+                if seconds_per_cell <= 0. { MAX_GRAVITY } else { 1. / (seconds_per_cell * FRAMES_PER_SECOND) }
+            },
+            Gravity::Nes => {
+                let index = usize::min(level as usize - 1, NES_FRAMES_PER_CELL.len() - 1);
+
+                1. / NES_FRAMES_PER_CELL[index] as f64
+            },
+            Gravity::Table(table) => {
+                let index = usize::min(level as usize - 1, table.len().saturating_sub(1));
+
+                table.get(index).copied().unwrap_or(0.)
+            },
+            Gravity::Fixed(gravity) => *gravity,
+        };
+
+        gravity.min(MAX_GRAVITY)
+    }
+}
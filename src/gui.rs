@@ -1,3 +1,6 @@
+use std::mem;
+use std::time::Duration;
+
 use sdl2::Sdl;
 use sdl2::rect::Rect;
 use sdl2::pixels::Color;
@@ -5,15 +8,20 @@ use sdl2::render::Canvas;
 use sdl2::video::Window;
 use sdl2::ttf::{Font, Sdl2TtfContext};
 
-use crate::game::{self, Game};
-use crate::{Cord, Pos, BLOCK_SIZE, GAME_POS, GAME_WIDTH, GAME_HEIGHT, FONT_CHAR_WIDTH, FONT_CHAR_HEIGHT};
+use crate::game::{Game, GameOverReason};
+use crate::mode::{Mode, SPLIT_LINES};
+use crate::{Cord, Pos, BLOCK_SIZE, PARTIAL_ROW_HEIGHT, GAME_POS, FONT_CHAR_WIDTH, FONT_CHAR_HEIGHT};
 use crate::tetros::TetroType;
 
 pub struct GUI<'a> {
     pub sdl_context: &'a Sdl,
     pub canvas: Canvas<Window>,
     pub font: Font<'a, 'static>,
-    pub game: Game
+    pub game: Game,
+    // drawn to the right of `game` in versus play
+    pub opponent: Option<Game>,
+    // shows a sliver of the lowest buffer row above the playfield
+    pub show_partial_row: bool
 }
 
 impl<'a> GUI<'a> {
@@ -22,36 +30,108 @@ impl<'a> GUI<'a> {
 
         let font = ttf_context.load_font("DOS-font.ttf", 128).unwrap();
 
-        let window = video_subsystem.window(window_title, BLOCK_SIZE as u32 * 17, BLOCK_SIZE as u32 * 22).build().unwrap();
+        let (window_width, window_height) = field_size(&game);
+
+        let window = video_subsystem.window(window_title, window_width as u32, window_height as u32).build().unwrap();
         let canvas = window.into_canvas().build().unwrap();
 
         Self {
             sdl_context,
             canvas,
             font,
-            game
+            game,
+            opponent: None,
+            show_partial_row: true
         }
     }
 
+    pub fn with_opponent(mut self, opponent: Game) -> Self {
+        let (field_width, field_height) = field_size(&self.game);
+        let (opponent_width, opponent_height) = field_size(&opponent);
+
+        self.canvas.window_mut().set_size((field_width + opponent_width) as u32, i32::max(field_height, opponent_height) as u32).unwrap();
+        self.opponent = Some(opponent);
+
+        self
+    }
+
     pub fn draw(&mut self) {
-        self.draw_tetro_box("HOLD", Pos(BLOCK_SIZE, BLOCK_SIZE * 2), self.game.hold_tetro);
-        self.draw_tetro_box("NEXT", Pos(BLOCK_SIZE, BLOCK_SIZE * 8), Some(self.game.get_next_tetro()));
+        self.draw_game();
+
+        // the opponent is drawn by the same code as `game`, just moved over to its own viewport
+        if let Some(mut opponent) = self.opponent.take() {
+            let (field_width, _) = field_size(&self.game);
+            let (opponent_width, opponent_height) = field_size(&opponent);
+
+            mem::swap(&mut self.game, &mut opponent);
+            self.canvas.set_viewport(Rect::new(field_width, 0, opponent_width as u32, opponent_height as u32));
+            self.draw_game();
+            self.canvas.set_viewport(None);
+            mem::swap(&mut self.game, &mut opponent);
+
+            self.opponent = Some(opponent);
+        }
+    }
+
+    fn draw_game(&mut self) {
+        self.draw_tetro_box("HOLD", Pos(BLOCK_SIZE, BLOCK_SIZE * 2), self.game.hold_tetro, !self.game.can_hold());
+        self.draw_tetro_box("NEXT", Pos(BLOCK_SIZE, BLOCK_SIZE * 8), Some(self.game.get_next_tetro()), false);
+
+        if self.game.combo > 0 {
+            self.draw_value_display("COMBO", Pos(BLOCK_SIZE, BLOCK_SIZE * 13), self.game.combo);
+        }
+        if self.game.back_to_back {
+            self.draw_text(Pos(BLOCK_SIZE * 4, BLOCK_SIZE * 13), "B2B");
+        }
+
+        match self.game.rules.mode {
+            Mode::Endless | Mode::Zen => {
+                self.draw_value_display("LEVEL", Pos(BLOCK_SIZE, BLOCK_SIZE * 16), self.game.level);
+                self.draw_value_display("SCORE", Pos(BLOCK_SIZE, BLOCK_SIZE * 19), self.game.score);
+            },
+            Mode::Marathon { level_cap } => {
+                self.draw_value_display("LEVEL", Pos(BLOCK_SIZE, BLOCK_SIZE * 16), format!("{}/{}", self.game.level.min(level_cap), level_cap));
+                self.draw_value_display("SCORE", Pos(BLOCK_SIZE, BLOCK_SIZE * 19), self.game.score);
+            },
+            Mode::Sprint { lines } => {
+                self.draw_value_display("LINES", Pos(BLOCK_SIZE, BLOCK_SIZE * 16), format!("{}/{}", self.game.lines, lines));
+                self.draw_value_display("TIME", Pos(BLOCK_SIZE, BLOCK_SIZE * 19), format_time(self.game.time));
+            },
+            Mode::Ultra { time_limit } => {
+                self.draw_value_display("TIME", Pos(BLOCK_SIZE, BLOCK_SIZE * 16), format_time(time_limit.saturating_sub(self.game.time)));
+                self.draw_value_display("SCORE", Pos(BLOCK_SIZE, BLOCK_SIZE * 19), self.game.score);
+            },
+            Mode::Dig { .. } => {
+                self.draw_value_display("GARBAGE", Pos(BLOCK_SIZE, BLOCK_SIZE * 16), self.game.garbage_lines);
+                self.draw_value_display("TIME", Pos(BLOCK_SIZE, BLOCK_SIZE * 19), format_time(self.game.time));
+            }
+        }
 
-        self.draw_value_display("LEVEL", Pos(BLOCK_SIZE, BLOCK_SIZE * 16), self.game.lines / 10 + 1);
-        self.draw_value_display("SCORE", Pos(BLOCK_SIZE, BLOCK_SIZE * 19), self.game.score);
+        let partial_row_height = if self.show_partial_row { PARTIAL_ROW_HEIGHT } else { 0 };
+        let game_width = self.game.blocks.width;
+        let game_height = self.game.blocks.height;
 
-        let game_border_rect = Rect::new(GAME_POS.0 - 4, GAME_POS.1 - 4, (BLOCK_SIZE * GAME_WIDTH + 8) as u32, (BLOCK_SIZE * GAME_HEIGHT + 8) as u32);
+        let game_border_rect = Rect::new(GAME_POS.0 - 4, GAME_POS.1 - partial_row_height - 4, (BLOCK_SIZE * game_width + 8) as u32, (BLOCK_SIZE * game_height + partial_row_height + 8) as u32);
         self.canvas.set_draw_color(Color::RGB(0, 0, 0));
         self.canvas.fill_rect(game_border_rect).unwrap();
 
-        for (i, block) in self.game.blocks.iter().enumerate() {
-            let cord = Cord(i as i32 % GAME_WIDTH, i as i32 / GAME_WIDTH);
+        // anything in the buffer rows gets cut off at the top of the playfield
+        let visible_rect = Rect::new(GAME_POS.0, GAME_POS.1 - partial_row_height, (BLOCK_SIZE * game_width) as u32, (BLOCK_SIZE * game_height + partial_row_height) as u32);
+        self.canvas.set_clip_rect(visible_rect);
+
+        for i in 0..game_width * (game_height + 1) {
+            let cord = Cord(i % game_width, i / game_width - 1);
+            let block = self.game.blocks.get(cord);
+            if cord.is_in_buffer() && !self.game.blocks.is_filled(cord) { continue }
+
             let pos = cord.pos();
 
             let mut block_colour = (Color::RGB(189, 195, 199), Color::RGB(181, 187, 191));
 
             if let Some(block) = block {
                 block_colour = block.colour();
+            } else if self.game.blocks.is_garbage(cord) {
+                block_colour = (Color::RGB(127, 140, 141), Color::RGB(99, 110, 114));
             }
 
             let border_size = BLOCK_SIZE / 10;
@@ -67,9 +147,9 @@ impl<'a> GUI<'a> {
 
         if self.game.is_playing {
             let mut ghost = self.game.dropping_tetro;
-            for i in self.game.dropping_tetro.cord.1..GAME_HEIGHT {
+            for i in self.game.dropping_tetro.cord.1..game_height {
                 ghost.cord.1 = i;
-                if game::is_tetro_colliding(self.game.blocks, ghost) { break }
+                if self.game.blocks.is_tetro_colliding(ghost) { break }
             }
             ghost.cord.1 -= 1;
 
@@ -78,6 +158,105 @@ impl<'a> GUI<'a> {
         }
 
         self.game.dropping_tetro.draw(&mut self.canvas, !self.game.is_playing);
+
+        self.canvas.set_clip_rect(None);
+
+        self.draw_garbage_meter();
+
+        if let Some(reason) = self.game.game_over_reason {
+            let text = match reason {
+                GameOverReason::BlockOut => "BLOCK OUT",
+                GameOverReason::LockOut => "LOCK OUT",
+                GameOverReason::PartialLockOut => "LOCK OUT",
+                GameOverReason::TopOut => "TOP OUT",
+                GameOverReason::Won => "WINNER",
+                GameOverReason::Completed => return self.draw_results(),
+            };
+
+            self.draw_banner(text);
+        }
+    }
+
+    // a bar rising along the left of the playfield, one block high for every pending garbage line
+    fn draw_garbage_meter(&mut self) {
+        let game_height = self.game.blocks.height;
+        let pending_lines = i32::min(self.game.pending_garbage.lines(), game_height);
+        if pending_lines == 0 { return }
+
+        let meter_width = BLOCK_SIZE / 4;
+        let meter_rect = Rect::new(
+            GAME_POS.0 - 8 - meter_width,
+            GAME_POS.1 + BLOCK_SIZE * (game_height - pending_lines),
+            meter_width as u32,
+            (BLOCK_SIZE * pending_lines) as u32
+        );
+
+        self.canvas.set_draw_color(Color::RGB(235, 59, 90));
+        self.canvas.fill_rect(meter_rect).unwrap();
+    }
+
+    fn results(&self) -> Vec<String> {
+        let mut results = vec!["FINISHED".to_string(), String::new()];
+
+        match self.game.rules.mode {
+            Mode::Marathon { level_cap } => {
+                results.push(format!("SCORE  {}", self.game.score));
+                results.push(format!("LINES  {}", self.game.lines));
+                results.push(format!("LEVEL  {level_cap}"));
+            },
+            Mode::Ultra { .. } => {
+                results.push(format!("SCORE  {}", self.game.score));
+                results.push(format!("LINES  {}", self.game.lines));
+            },
+            Mode::Dig { rows, .. } => results.push(format!("DUG    {}", rows.min(self.game.blocks.height))),
+            _ => ()
+        }
+
+        results.extend([
+            format!("TIME   {}", format_time(self.game.time)),
+            format!("PIECES {}", self.game.pieces),
+            format!("PPS    {:.2}", self.game.pieces_per_second()),
+            format!("APM    {:.1}", self.game.attack_per_minute()),
+        ]);
+
+        if !self.game.splits.is_empty() {
+            results.push(String::new());
+
+            let mut last_split = Duration::ZERO;
+            for (i, split) in self.game.splits.iter().enumerate() {
+                results.push(format!("{:<3} {} +{:.2}", (i as i32 + 1) * SPLIT_LINES, format_time(*split), (*split - last_split).as_secs_f64()));
+                last_split = *split;
+            }
+        }
+
+        results
+    }
+
+    // covers the playfield with one line of text per row
+    fn draw_results(&mut self) {
+        let results = self.results();
+        let game_width = self.game.blocks.width;
+        let game_height = self.game.blocks.height;
+
+        self.canvas.set_draw_color(Color::RGB(0, 0, 0));
+        self.canvas.fill_rect(Rect::new(GAME_POS.0, GAME_POS.1, (BLOCK_SIZE * game_width) as u32, (BLOCK_SIZE * game_height) as u32)).unwrap();
+
+        for (i, line) in results.iter().enumerate().take(game_height as usize - 1) {
+            if line.is_empty() { continue }
+
+            self.draw_text(Pos(GAME_POS.0 + BLOCK_SIZE / 2, GAME_POS.1 + BLOCK_SIZE / 2 + BLOCK_SIZE * i as i32), line);
+        }
+    }
+
+    fn draw_banner(&mut self, text: &str) {
+        let game_width = self.game.blocks.width;
+        let banner_pos = Pos(GAME_POS.0, GAME_POS.1 + BLOCK_SIZE * (self.game.blocks.height / 2 - 1));
+
+        self.canvas.set_draw_color(Color::RGB(0, 0, 0));
+        self.canvas.fill_rect(Rect::new(banner_pos.0, banner_pos.1, (BLOCK_SIZE * game_width) as u32, BLOCK_SIZE as u32 * 2)).unwrap();
+
+        let text_pos = Pos(banner_pos.0 + (BLOCK_SIZE * game_width - FONT_CHAR_WIDTH * text.len() as i32) / 2, banner_pos.1 + BLOCK_SIZE / 2);
+        self.draw_text(text_pos, text);
     }
 
     fn draw_text(&mut self, pos: Pos, text: &str) {
@@ -93,7 +272,7 @@ impl<'a> GUI<'a> {
         self.canvas.copy(&texture, None, Some(title_rect)).unwrap();
     }
 
-    fn draw_tetro_box(&mut self, box_title: &'static str, box_pos: Pos, tetro: Option<TetroType>) {
+    fn draw_tetro_box(&mut self, box_title: &'static str, box_pos: Pos, tetro: Option<TetroType>, greyed_out: bool) {
         let mut title_pos = box_pos;
         title_pos.1 -= BLOCK_SIZE;
         self.draw_text(title_pos, box_title);
@@ -102,11 +281,11 @@ impl<'a> GUI<'a> {
         self.canvas.fill_rect(Rect::new(box_pos.0, box_pos.1, BLOCK_SIZE as u32 * 4, BLOCK_SIZE as u32 * 4)).unwrap();
 
         if let Some(tetro) = tetro {
-            tetro.draw_centered(&mut self.canvas, box_pos);
+            tetro.draw_centered(&mut self.canvas, box_pos, greyed_out);
         }
     }
 
-    fn draw_value_display(&mut self, display_title: &'static str, display_pos: Pos, display_value: i32) {
+    fn draw_value_display(&mut self, display_title: &'static str, display_pos: Pos, display_value: impl ToString) {
         self.draw_text(display_pos, display_title);
 
         let mut value_pos = display_pos;
@@ -116,3 +295,16 @@ impl<'a> GUI<'a> {
     }
 }
 
+
+// the side panel needs 20 rows of room even when the board is shorter
+fn field_size(game: &Game) -> (i32, i32) {
+    (
+        GAME_POS.0 + BLOCK_SIZE * (game.blocks.width + 1),
+        GAME_POS.1 + BLOCK_SIZE * (i32::max(game.blocks.height, 20) + 1)
+    )
+}
+
+// minutes, seconds and milliseconds
+pub fn format_time(time: Duration) -> String {
+    format!("{}:{:02}.{:03}", time.as_secs() / 60, time.as_secs() % 60, time.subsec_millis())
+}
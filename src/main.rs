@@ -1,11 +1,19 @@
 use std::time::{Duration, Instant};
 use std::thread;
+use std::env;
 use sdl2::pixels::Color;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
+use rand::{thread_rng, Rng};
 use tetros::game::Game;
+use tetros::randomizer;
+use tetros::scoring;
+use tetros::mode::{self, Mode};
+use tetros::rules::Rules;
 use tetros::controls;
+use tetros::tetros::TetroType;
 use tetros::gui::GUI;
+use tetros::cli::flag;
 
 #[derive(Copy, Clone, Debug)]
 struct Key(Keycode, Instant);
@@ -13,8 +21,9 @@ struct Key(Keycode, Instant);
 impl Key {
     fn repeat_key(keycode: Keycode, game: &mut Game) {
         match keycode {
-            Keycode::Q => controls::rotate_tetro(game, -1),
-            Keycode::E => controls::rotate_tetro(game, 1),
+            Keycode::Q => { controls::rotate_tetro(game, -1); },
+            Keycode::E => { controls::rotate_tetro(game, 1); },
+            Keycode::W => { controls::rotate_tetro(game, 2); },
             Keycode::A => controls::shift_tetro(game, -1),
             Keycode::D => controls::shift_tetro(game, 1),
             _ => ()
@@ -23,7 +32,56 @@ impl Key {
 }
 
 fn main() {
-    let game = Game::new();
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let seed = match flag(&args, "--seed") {
+        Some(seed) => seed.parse().expect("invalid seed"),
+        None => thread_rng().gen()
+    };
+    let randomizer = flag(&args, "--randomizer").unwrap_or("7bag".to_string());
+    let scoring = flag(&args, "--scoring").unwrap_or("guideline".to_string());
+    let mode = flag(&args, "--mode").unwrap_or("endless".to_string());
+
+    let mut rules = Rules {
+        scoring: scoring::from_name(&scoring).expect("unknown scoring system"),
+        mode: mode::from_name(&mode).expect("unknown mode"),
+        ..Rules::default()
+    };
+
+    if let Some(width) = flag(&args, "--width") {
+        rules.board_width = width.parse().expect("invalid board width");
+    }
+    if let Some(height) = flag(&args, "--height") {
+        rules.board_height = height.parse().expect("invalid board height");
+    }
+    if let Some(level) = flag(&args, "--level") {
+        rules.start_level = level.parse().expect("invalid start level");
+    }
+    if let Some(level_cap) = flag(&args, "--level-cap") {
+        if let Mode::Marathon { level_cap: cap } = &mut rules.mode {
+            *cap = level_cap.parse().expect("invalid level cap");
+        }
+    }
+    if let Some(lines) = flag(&args, "--lines") {
+        if let Mode::Sprint { lines: goal } = &mut rules.mode {
+            *goal = lines.parse().expect("invalid line goal");
+        }
+    }
+    if let Some(seconds) = flag(&args, "--time") {
+        if let Mode::Ultra { time_limit } = &mut rules.mode {
+            *time_limit = Duration::from_secs_f64(seconds.parse().expect("invalid time limit"));
+        }
+    }
+    if let Mode::Dig { rows, messiness } = &mut rules.mode {
+        if let Some(garbage_rows) = flag(&args, "--rows") {
+            *rows = garbage_rows.parse().expect("invalid garbage rows");
+        }
+        if let Some(garbage_messiness) = flag(&args, "--messiness") {
+            *messiness = garbage_messiness.parse().expect("invalid garbage messiness");
+        }
+    }
+
+    let game = Game::with_rules(randomizer::from_name(&randomizer, seed).expect("unknown randomizer"), rules);
 
     let sdl_context = sdl2::init().unwrap();
     let ttf_context = sdl2::ttf::init().unwrap();
@@ -32,9 +90,13 @@ fn main() {
     let mut event_pump = gui.sdl_context.event_pump().unwrap();
 
     let mut keys_down: Vec<Key> = vec![];
+    let mut last_frame = Instant::now();
 
     'running: loop {
-        if gui.game.is_playing { gui.game.next_frame(); };
+        let frame_delta = last_frame.elapsed();
+        last_frame = Instant::now();
+
+        gui.game.next_frame(frame_delta);
 
         gui.canvas.clear();
         gui.draw();
@@ -53,28 +115,29 @@ fn main() {
                 Event::Quit {..} => {
                     break 'running
                 },
+                // undo and redo also bring back games that were topped out
+                Event::KeyDown { keycode: Some(Keycode::U), repeat: false, .. } if gui.game.rules.mode.is_practice() => { gui.game.undo(); },
+                Event::KeyDown { keycode: Some(Keycode::R), repeat: false, .. } if gui.game.rules.mode.is_practice() => { gui.game.redo(); },
                 Event::KeyDown { keycode: Some(keycode), .. }
-                    if gui.game.is_playing => {
+                    if gui.game.is_playing && !keys_down.iter().any(|key| key.0 == keycode) => {
 
-                    if keys_down.iter().position(|key| key.0 == keycode).is_none() {
-                        keys_down.push(Key(keycode, Instant::now()));
-
-                        match keycode {
-                            Keycode::S => gui.game.is_soft_dropping = true,
-                            Keycode::Return => controls::hard_drop(&mut gui.game),
-                            Keycode::F => controls::hold_tetro(&mut gui.game),
-                            _ => Key::repeat_key(keycode, &mut gui.game)
-                        }
+                    keys_down.push(Key(keycode, Instant::now()));
 
+                    match keycode {
+                        Keycode::S => gui.game.is_soft_dropping = true,
+                        Keycode::Return => controls::hard_drop(&mut gui.game),
+                        Keycode::F => controls::hold_tetro(&mut gui.game),
+                        Keycode::Num1 | Keycode::Num2 | Keycode::Num3 | Keycode::Num4 | Keycode::Num5 | Keycode::Num6 | Keycode::Num7 => {
+                            let index = keycode as usize - Keycode::Num1 as usize;
+                            gui.game.choose_next_tetro(TetroType::from_index(index).unwrap());
+                        },
+                        _ => Key::repeat_key(keycode, &mut gui.game)
                     }
                 },
                 Event::KeyUp { keycode: Some(keycode), .. } => {
                     keys_down.retain(|key| key.0 != keycode);
 
-                    match keycode {
-                        Keycode::S => gui.game.is_soft_dropping = false,
-                        _ => ()
-                    }
+                    if keycode == Keycode::S { gui.game.is_soft_dropping = false }
                 },
                 _ => ()
             }
@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use serde::{Serialize, Deserialize};
+
+use crate::game::Game;
+
+pub const SPRINT_LINES: i32 = 40;
+pub const ULTRA_TIME: Duration = Duration::from_secs(2 * 60);
+pub const MARATHON_LEVEL_CAP: i32 = 15;
+pub const DIG_ROWS: i32 = 10;
+pub const DIG_MESSINESS: f64 = 1.;
+// a split is taken every time this many more lines have been cleared
+pub const SPLIT_LINES: i32 = 10;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Mode {
+    // plays until topping out
+    #[default]
+    Endless,
+    // play through the levels up to and including `level_cap`
+    Marathon { level_cap: i32 },
+    // clear the given amount of lines as fast as possible
+    Sprint { lines: i32 },
+    // score as much as possible before the time runs out
+    Ultra { time_limit: Duration },
+    // clear the given amount of garbage rows the board starts with, see `garbage::GarbageHoles`
+    Dig { rows: i32, messiness: f64 },
+    // no gravity or lock delay, placements can be undone and the next tetro picked by hand
+    Zen,
+}
+
+impl Mode {
+    // whether the game has reached the goal of the mode, checked every frame and after every lock
+    pub fn is_completed(&self, game: &Game) -> bool {
+        match self {
+            Mode::Endless | Mode::Zen => false,
+            Mode::Marathon { level_cap } => game.level > *level_cap,
+            Mode::Sprint { lines } => game.lines >= *lines,
+            Mode::Ultra { time_limit } => game.time >= *time_limit,
+            Mode::Dig { .. } => game.garbage_lines == 0,
+        }
+    }
+
+    pub fn is_practice(&self) -> bool {
+        matches!(self, Mode::Zen)
+    }
+
+    pub fn time_limit(&self) -> Option<Duration> {
+        match self {
+            Mode::Ultra { time_limit } => Some(*time_limit),
+            _ => None
+        }
+    }
+}
+
+pub fn from_name(name: &str) -> Option<Mode> {
+    match name {
+        "endless" => Some(Mode::Endless),
+        "marathon" => Some(Mode::Marathon { level_cap: MARATHON_LEVEL_CAP }),
+        "sprint" => Some(Mode::Sprint { lines: SPRINT_LINES }),
+        "ultra" => Some(Mode::Ultra { time_limit: ULTRA_TIME }),
+        "zen" => Some(Mode::Zen),
+        "dig" => Some(Mode::Dig { rows: DIG_ROWS, messiness: DIG_MESSINESS }),
+        _ => None
+    }
+}
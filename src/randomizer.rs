@@ -0,0 +1,235 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::TETRO_TYPES_AMOUNT;
+use crate::tetros::TetroType;
+
+pub trait Randomizer: Send {
+    fn next_tetro(&mut self) -> TetroType;
+    fn seed(&self) -> u64;
+    // the name `from_name` builds this randomizer from
+    fn name(&self) -> String;
+    fn clone_box(&self) -> Box<dyn Randomizer>;
+}
+
+pub fn from_name(name: &str, seed: u64) -> Option<Box<dyn Randomizer>> {
+    match name {
+        "7bag" => Some(Box::new(Bag::seven(seed))),
+        "14bag" => Some(Box::new(Bag::fourteen(seed))),
+        "uniform" => Some(Box::new(Uniform::new(seed))),
+        "nes" => Some(Box::new(NesReroll::new(seed))),
+        "tgm" => Some(Box::new(TgmHistory::new(seed, 4))),
+        _ => {
+            // any other bag size as `<tetros>bag` and any other amount of rolls as `tgm<rolls>`
+            if let Some(bag_tetros) = name.strip_suffix("bag").and_then(|size| size.parse::<usize>().ok()) {
+                if bag_tetros > 0 && bag_tetros % TETRO_TYPES_AMOUNT == 0 {
+                    return Some(Box::new(Bag::new(seed, bag_tetros / TETRO_TYPES_AMOUNT)));
+                }
+            }
+            if let Some(rolls) = name.strip_prefix("tgm").and_then(|rolls| rolls.parse().ok()) {
+                return Some(Box::new(TgmHistory::new(seed, rolls)));
+            }
+
+            None
+        }
+    }
+}
+
+impl Clone for Box<dyn Randomizer> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+// shuffles `bag_size` copies of every tetro together and deals them out before refilling
+#[derive(Clone)]
+pub struct Bag {
+    seed: u64,
+    rng: StdRng,
+    bag_size: usize,
+    bag: Vec<TetroType>
+}
+
+impl Bag {
+    pub fn new(seed: u64, bag_size: usize) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            bag_size,
+            bag: vec![]
+        }
+    }
+
+    pub fn seven(seed: u64) -> Self {
+        Self::new(seed, 1)
+    }
+
+    pub fn fourteen(seed: u64) -> Self {
+        Self::new(seed, 2)
+    }
+}
+
+impl Randomizer for Bag {
+    fn next_tetro(&mut self) -> TetroType {
+        if self.bag.is_empty() {
+            for _ in 0..self.bag_size {
+                self.bag.append(&mut TetroType::random_set(&mut self.rng));
+            }
+
+            if self.bag_size > 1 {
+                self.bag.shuffle(&mut self.rng);
+            }
+        }
+
+        self.bag.pop().unwrap()
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn name(&self) -> String {
+        format!("{}bag", self.bag_size * TETRO_TYPES_AMOUNT)
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Clone)]
+pub struct Uniform {
+    seed: u64,
+    rng: StdRng
+}
+
+impl Uniform {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed)
+        }
+    }
+}
+
+impl Randomizer for Uniform {
+    fn next_tetro(&mut self) -> TetroType {
+        TetroType::random_tetro(&mut self.rng)
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn name(&self) -> String {
+        "uniform".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+// rolls an 8 sided die where the 8th side (or a repeat of the last tetro) rerolls once
+#[derive(Clone)]
+pub struct NesReroll {
+    seed: u64,
+    rng: StdRng,
+    last_tetro: Option<TetroType>
+}
+
+impl NesReroll {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            last_tetro: None
+        }
+    }
+}
+
+impl Randomizer for NesReroll {
+    fn next_tetro(&mut self) -> TetroType {
+        let roll = self.rng.gen_range(0..=TETRO_TYPES_AMOUNT);
+
+        let tetro = match TetroType::from_index(roll) {
+            Some(tetro) if Some(tetro) != self.last_tetro => tetro,
+            _ => TetroType::random_tetro(&mut self.rng)
+        };
+
+        self.last_tetro = Some(tetro);
+
+        tetro
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn name(&self) -> String {
+        "nes".to_string()
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+// rolls up to `rolls` times in total while the tetro is one of the last four dealt
+#[derive(Clone)]
+pub struct TgmHistory {
+    seed: u64,
+    rng: StdRng,
+    rolls: usize,
+    history: [TetroType; 4],
+    is_first: bool
+}
+
+impl TgmHistory {
+    pub fn new(seed: u64, rolls: usize) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            rolls,
+            history: [TetroType::Z; 4],
+            is_first: true
+        }
+    }
+}
+
+impl Randomizer for TgmHistory {
+    fn next_tetro(&mut self) -> TetroType {
+        let mut tetro = TetroType::random_tetro(&mut self.rng);
+
+        if self.is_first {
+            // the first tetro is never an S, Z or O so the opening is always playable
+            while matches!(tetro, TetroType::S | TetroType::Z | TetroType::O) {
+                tetro = TetroType::random_tetro(&mut self.rng);
+            }
+            self.is_first = false;
+        } else {
+            for _ in 1..self.rolls {
+                if !self.history.contains(&tetro) { break }
+                tetro = TetroType::random_tetro(&mut self.rng);
+            }
+        }
+
+        self.history.rotate_right(1);
+        self.history[0] = tetro;
+
+        tetro
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn name(&self) -> String {
+        if self.rolls == 4 { "tgm".to_string() } else { format!("tgm{}", self.rolls) }
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
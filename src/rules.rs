@@ -0,0 +1,60 @@
+use crate::{GAME_WIDTH, GAME_HEIGHT, GAME_BUFFER_HEIGHT};
+use crate::srs::KickTable180;
+use crate::scoring::{ScoringSystem, Guideline};
+use crate::gravity::Gravity;
+use crate::mode::Mode;
+use crate::attack::AttackTable;
+
+// what moving or rotating a tetro that is resting on the stack does to its lock delay
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LockDown {
+    Infinite,
+    // resets up to the given amount of times, refilled when the tetro reaches a new lowest row
+    ExtendedPlacement(u32),
+    // only reaching a new lowest row resets the lock delay
+    StepReset,
+}
+
+impl Default for LockDown {
+    fn default() -> Self {
+        LockDown::ExtendedPlacement(15)
+    }
+}
+
+#[derive(Clone)]
+pub struct Rules {
+    pub board_width: i32,
+    pub board_height: i32,
+    pub buffer_height: i32,
+    pub kicks_180: KickTable180,
+    pub scoring: Box<dyn ScoringSystem>,
+    pub gravity: Gravity,
+    pub lock_down: LockDown,
+    pub hold_enabled: bool,
+    pub partial_lock_out: bool,
+    pub start_level: i32,
+    pub attack_table: AttackTable,
+    // see `garbage::GarbageHoles`
+    pub garbage_messiness: f64,
+    pub mode: Mode
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            board_width: GAME_WIDTH,
+            board_height: GAME_HEIGHT,
+            buffer_height: GAME_BUFFER_HEIGHT,
+            kicks_180: KickTable180::default(),
+            scoring: Box::new(Guideline),
+            gravity: Gravity::default(),
+            lock_down: LockDown::default(),
+            hold_enabled: true,
+            partial_lock_out: false,
+            start_level: 1,
+            attack_table: AttackTable::default(),
+            garbage_messiness: 0.,
+            mode: Mode::default()
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use crate::game::{LockResult, TSpin};
+
+pub trait ScoringSystem: Send {
+    fn line_clear(&self, lock: &LockResult, level: i32) -> i32;
+
+    fn soft_drop(&self, _cells: i32) -> i32 {
+        0
+    }
+
+    fn hard_drop(&self, _cells: i32) -> i32 {
+        0
+    }
+
+    fn clone_box(&self) -> Box<dyn ScoringSystem>;
+}
+
+impl Clone for Box<dyn ScoringSystem> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+pub fn from_name(name: &str) -> Option<Box<dyn ScoringSystem>> {
+    match name {
+        "guideline" => Some(Box::new(Guideline)),
+        "nes" => Some(Box::new(Nes)),
+        "bps" => Some(Box::new(Bps)),
+        "sega" => Some(Box::new(Sega)),
+        _ => None
+    }
+}
+
+// modern guideline, everything except drops is multiplied by the level
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Guideline;
+
+impl ScoringSystem for Guideline {
+    fn line_clear(&self, lock: &LockResult, level: i32) -> i32 {
+        let clear_score = match (lock.t_spin, lock.lines_cleared) {
+            (TSpin::None, 1) => 100,
+            (TSpin::None, 2) => 300,
+            (TSpin::None, 3) => 500,
+            (TSpin::None, 4) => 800,
+            (TSpin::Mini, 0) => 100,
+            (TSpin::Mini, 1) => 200,
+            (TSpin::Mini, 2) => 400,
+            (TSpin::Full, 0) => 400,
+            (TSpin::Full, 1) => 800,
+            (TSpin::Full, 2) => 1200,
+            (TSpin::Full, 3) => 1600,
+            _ => 0
+        };
+
+        let mut score = if lock.back_to_back { clear_score * 3 / 2 } else { clear_score };
+
+        if lock.combo > 0 {
+            score += 50 * lock.combo;
+        }
+
+        if lock.perfect_clear {
+            score += match (lock.lines_cleared, lock.back_to_back) {
+                (4, true) => 3200,
+                (1, _) => 800,
+                (2, _) => 1200,
+                (3, _) => 1800,
+                (4, _) => 2000,
+                _ => 0
+            };
+        }
+
+        score * level
+    }
+
+    fn soft_drop(&self, cells: i32) -> i32 {
+        cells
+    }
+
+    fn hard_drop(&self, cells: i32) -> i32 {
+        cells * 2
+    }
+
+    fn clone_box(&self) -> Box<dyn ScoringSystem> {
+        Box::new(*self)
+    }
+}
+
+// NES levels start at 0, so multiplying by our level is the same as its level + 1
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Nes;
+
+impl ScoringSystem for Nes {
+    fn line_clear(&self, lock: &LockResult, level: i32) -> i32 {
+        let clear_score = match lock.lines_cleared {
+            1 => 40,
+            2 => 100,
+            3 => 300,
+            4 => 1200,
+            _ => 0
+        };
+
+        clear_score * level
+    }
+
+    fn soft_drop(&self, cells: i32) -> i32 {
+        cells
+    }
+
+    fn clone_box(&self) -> Box<dyn ScoringSystem> {
+        Box::new(*self)
+    }
+}
+
+// Tetris by BPS doesn't scale with the level at all
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Bps;
+
+impl ScoringSystem for Bps {
+    fn line_clear(&self, lock: &LockResult, _level: i32) -> i32 {
+        match lock.lines_cleared {
+            1 => 40,
+            2 => 100,
+            3 => 300,
+            4 => 1200,
+            _ => 0
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn ScoringSystem> {
+        Box::new(*self)
+    }
+}
+
+// Sega multiplies by one more every second level, capped at five times
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sega;
+
+impl ScoringSystem for Sega {
+    fn line_clear(&self, lock: &LockResult, level: i32) -> i32 {
+        let clear_score = match lock.lines_cleared {
+            1 => 100,
+            2 => 400,
+            3 => 900,
+            4 => 2000,
+            _ => 0
+        };
+
+        clear_score * i32::min((level - 1) / 2 + 1, 5)
+    }
+
+    fn clone_box(&self) -> Box<dyn ScoringSystem> {
+        Box::new(*self)
+    }
+}
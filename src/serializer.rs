@@ -1,54 +1,141 @@
-use std::{time::Instant, fs, process::Command};
+use std::{fs, process::Command};
 
 use serde::{Serialize, Deserialize};
 
 use crate::{
-    game::Game,
+    Cord,
+    board::Board,
+    game::{Game, LINES_PER_LEVEL},
+    rules::Rules,
+    mode::Mode,
+    randomizer,
     tetros::{TetroType, GameTetro},
     GAME_WIDTH,
-    GAME_HEIGHT
+    GAME_HEIGHT,
+    GAME_BUFFER_HEIGHT
 };
 
+fn default_randomizer() -> String {
+    "7bag".to_string()
+}
+
+fn default_start_level() -> i32 {
+    1
+}
+
+fn default_width() -> i32 {
+    GAME_WIDTH
+}
+
+fn default_height() -> i32 {
+    GAME_HEIGHT
+}
+
+fn default_buffer_height() -> i32 {
+    GAME_BUFFER_HEIGHT
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GameData {
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default = "default_randomizer")]
+    pub randomizer: String,
+    // how far into the randomizer the game is, so it deals the same tetros after loading
+    #[serde(default)]
+    pub tetros_dealt: usize,
+    #[serde(default)]
+    pub mode: Mode,
+    #[serde(default = "default_start_level")]
+    pub start_level: i32,
     pub lines: i32,
     pub score: i32,
     pub tetro_queue: Vec<TetroType>,
     pub dropping_tetro: GameTetro,
     pub hold_tetro: Option<TetroType>,
-    pub blocks: Vec<Option<TetroType>>
+    #[serde(default = "default_width")]
+    pub width: i32,
+    #[serde(default = "default_height")]
+    pub height: i32,
+    #[serde(default = "default_buffer_height")]
+    pub buffer_height: i32,
+    pub blocks: Vec<Option<TetroType>>,
+    #[serde(default)]
+    pub garbage: Vec<Cord>,
+    // garbage rows still in the stack, which is what dig games count down
+    #[serde(default)]
+    pub garbage_lines: i32
 }
 
 impl GameData {
     pub fn from_game(game: &Game) -> Self {
         Self {
+            seed: game.randomizer.seed(),
+            randomizer: game.randomizer.name(),
+            tetros_dealt: game.tetros_dealt,
+            mode: game.rules.mode,
+            start_level: game.rules.start_level,
             lines: game.lines,
             score: game.score,
             tetro_queue: game.tetro_queue.clone(),
             dropping_tetro: game.dropping_tetro,
             hold_tetro: game.hold_tetro,
-            blocks: game.blocks.iter().cloned().collect()
+            width: game.blocks.width,
+            height: game.blocks.height,
+            buffer_height: game.blocks.buffer_height,
+            blocks: game.blocks.to_vec(),
+            garbage: (-game.blocks.buffer_height..game.blocks.height)
+                .flat_map(|y| (0..game.blocks.width).map(move |x| Cord(x, y)))
+                .filter(|cord| game.blocks.is_garbage(*cord))
+                .collect(),
+            garbage_lines: game.garbage_lines
         }
     }
 
     pub fn to_game(&self) -> Game {
-        let mut blocks = [None; (GAME_WIDTH * GAME_HEIGHT) as usize];
+        let mut blocks = Board::new(self.width, self.height, self.buffer_height);
+        let blocks_amount = (self.width * blocks.rows()) as usize;
+
+        // older files don't have the buffer rows, so line the blocks up from the bottom
+        let offset = blocks_amount.saturating_sub(self.blocks.len());
+        for (i, game_block) in self.blocks.iter().take(blocks_amount).enumerate() {
+            let i = (offset + i) as i32;
+            blocks.set(Cord(i % self.width, i / self.width - self.buffer_height), *game_block);
+        }
+        for cord in &self.garbage {
+            blocks.set_garbage(*cord);
+        }
+
+        let rules = Rules {
+            board_width: self.width,
+            board_height: self.height,
+            buffer_height: self.buffer_height,
+            mode: self.mode,
+            start_level: self.start_level,
+            ..Rules::default()
+        };
+
+        let new_randomizer = || randomizer::from_name(&self.randomizer, self.seed).expect("invalid randomizer");
 
-        for (i, game_block) in self.blocks.iter().enumerate() {
-            blocks[i] = game_block.clone();
+        // older files don't save how far in the randomizer was, those start over from the seed
+        let mut game_randomizer = new_randomizer();
+        for _ in 0..self.tetros_dealt {
+            game_randomizer.next_tetro();
         }
 
         Game {
+            randomizer: game_randomizer,
+            tetros_dealt: self.tetros_dealt,
             lines: self.lines,
+            level: self.start_level + self.lines / LINES_PER_LEVEL,
             score: self.score,
             dropping_tetro: self.dropping_tetro,
             tetro_queue: self.tetro_queue.clone(),
             hold_tetro: self.hold_tetro,
             blocks,
-            is_soft_dropping: false,
-            last_drop_timing: Instant::now(),
-            lock_delay: Instant::now(),
-            is_playing: false
+            garbage_lines: self.garbage_lines,
+            is_playing: false,
+            ..Game::with_rules(new_randomizer(), rules)
         }
     }
 }
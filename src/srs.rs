@@ -0,0 +1,80 @@
+use crate::Cord;
+use crate::tetros::TetroType;
+
+// kick offsets are in game cords, so a positive y moves the tetro down
+
+const JLSTZ_KICKS: [[[Cord; 5]; 2]; 4] = [
+    [
+        [Cord(0, 0), Cord(-1, 0), Cord(-1, -1), Cord(0, 2), Cord(-1, 2)],
+        [Cord(0, 0), Cord(1, 0), Cord(1, -1), Cord(0, 2), Cord(1, 2)],
+    ],
+    [
+        [Cord(0, 0), Cord(1, 0), Cord(1, 1), Cord(0, -2), Cord(1, -2)],
+        [Cord(0, 0), Cord(1, 0), Cord(1, 1), Cord(0, -2), Cord(1, -2)],
+    ],
+    [
+        [Cord(0, 0), Cord(1, 0), Cord(1, -1), Cord(0, 2), Cord(1, 2)],
+        [Cord(0, 0), Cord(-1, 0), Cord(-1, -1), Cord(0, 2), Cord(-1, 2)],
+    ],
+    [
+        [Cord(0, 0), Cord(-1, 0), Cord(-1, 1), Cord(0, -2), Cord(-1, -2)],
+        [Cord(0, 0), Cord(-1, 0), Cord(-1, 1), Cord(0, -2), Cord(-1, -2)],
+    ],
+];
+
+const I_KICKS: [[[Cord; 5]; 2]; 4] = [
+    [
+        [Cord(0, 0), Cord(-2, 0), Cord(1, 0), Cord(-2, 1), Cord(1, -2)],
+        [Cord(0, 0), Cord(-1, 0), Cord(2, 0), Cord(-1, -2), Cord(2, 1)],
+    ],
+    [
+        [Cord(0, 0), Cord(-1, 0), Cord(2, 0), Cord(-1, -2), Cord(2, 1)],
+        [Cord(0, 0), Cord(2, 0), Cord(-1, 0), Cord(2, -1), Cord(-1, 2)],
+    ],
+    [
+        [Cord(0, 0), Cord(2, 0), Cord(-1, 0), Cord(2, -1), Cord(-1, 2)],
+        [Cord(0, 0), Cord(1, 0), Cord(-2, 0), Cord(1, 2), Cord(-2, -1)],
+    ],
+    [
+        [Cord(0, 0), Cord(1, 0), Cord(-2, 0), Cord(1, 2), Cord(-2, -1)],
+        [Cord(0, 0), Cord(-2, 0), Cord(1, 0), Cord(-2, 1), Cord(1, -2)],
+    ],
+];
+
+const O_KICKS: [Cord; 1] = [Cord(0, 0)];
+
+// 180 kicks are shared by every tetro type and indexed by the starting rotation
+#[derive(Clone, Copy, Debug)]
+pub struct KickTable180(pub [&'static [Cord]; 4]);
+
+impl KickTable180 {
+    pub const IN_PLACE: KickTable180 = KickTable180([&O_KICKS; 4]);
+
+    pub const SRS_PLUS: KickTable180 = KickTable180([
+        &[Cord(0, 0), Cord(0, -1), Cord(1, -1), Cord(-1, -1), Cord(1, 0), Cord(-1, 0)],
+        &[Cord(0, 0), Cord(1, 0), Cord(1, -2), Cord(1, -1), Cord(0, -2), Cord(0, -1)],
+        &[Cord(0, 0), Cord(0, 1), Cord(-1, 1), Cord(1, 1), Cord(-1, 0), Cord(1, 0)],
+        &[Cord(0, 0), Cord(-1, 0), Cord(-1, -2), Cord(-1, -1), Cord(0, -2), Cord(0, -1)],
+    ]);
+
+    pub fn kicks(&self, rotation: usize) -> &'static [Cord] {
+        self.0[rotation]
+    }
+}
+
+impl Default for KickTable180 {
+    fn default() -> Self {
+        KickTable180::SRS_PLUS
+    }
+}
+
+// returns the offsets to test, in order, when rotating a quarter turn from `rotation`
+pub fn kicks(tetro_type: TetroType, rotation: usize, clockwise: bool) -> &'static [Cord] {
+    let direction = if clockwise { 0 } else { 1 };
+
+    match tetro_type {
+        TetroType::I => &I_KICKS[rotation][direction],
+        TetroType::O => &O_KICKS,
+        _ => &JLSTZ_KICKS[rotation][direction],
+    }
+}
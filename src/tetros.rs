@@ -1,13 +1,13 @@
 use rand::seq::SliceRandom;
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use sdl2::pixels::Color;
 use sdl2::rect::Rect;
 use sdl2::render::{Canvas, RenderTarget};
 use serde::{Serialize, Deserialize};
 
-use crate::{Cord, Pos, BLOCK_SIZE, GAME_WIDTH};
+use crate::{Cord, Pos, BLOCK_SIZE, TETRO_TYPES_AMOUNT};
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TetroType {
     I,
     J,
@@ -18,48 +18,108 @@ pub enum TetroType {
     Z,
 }
 
-impl TetroType {
-    pub fn shape(&self, rotation: usize) -> u16 {
-        //let mut shape: u16 = match self {
-        //TetroType::I => 0b0000111100000000,
-        //TetroType::J => 0b100111000,
-        //TetroType::L => 0b001111000,
-        //TetroType::O => 0b1111,
-        //TetroType::S => 0b011110000,
-        //TetroType::T => 0b010111000,
-        //TetroType::Z => 0b110011000,
-        //};
-
-        let mut shape: u16 = match self {
-            TetroType::I => 0b0000000011110000,
-            TetroType::J => 0b000111001,
-            TetroType::L => 0b000111100,
-            TetroType::O => 0b1111,
-            TetroType::S => 0b000011110,
-            TetroType::T => 0b000111010,
-            TetroType::Z => 0b000110011,
-        };
-
-        let size = self.shape_size();
-
-        for _ in 0..rotation {
-            let mut rotated_shape = shape;
-
-            for i in 0..size * size {
-                let bit = shape >> i & 1;
-
-                let new_x = size - 1 - i as i32 / size;
-                let new_y = i as i32 % size;
-
-                let new_index = (new_x + new_y * size) as usize;
-
-                rotated_shape = (rotated_shape & !(1 << new_index)) | (bit << new_index);
-            }
+// the spawn orientation of every tetro type, in `TetroType` order, with bit i being the
+// block at x = i % size, y = i / size
+const SPAWN_SHAPES: [(u16, i32); TETRO_TYPES_AMOUNT] = [
+    (0b0000000011110000, 4),
+    (0b000111001, 3),
+    (0b000111100, 3),
+    (0b1111, 2),
+    (0b000011110, 3),
+    (0b000111010, 3),
+    (0b000110011, 3),
+];
+
+// everything about a tetro type in one of its rotations, worked out once at compile time
+#[derive(Copy, Clone, Debug)]
+pub struct RotationState {
+    pub shape: u16,
+    // the shape split into one bitmask per row, bit x set for a block in column x
+    pub rows: [u16; 4],
+    pub cells: [Cord; 4],
+    // the top left and bottom right blocks of the bounding box, relative to the shape
+    pub min: Cord,
+    pub max: Cord,
+}
+
+static ROTATIONS: [[RotationState; 4]; TETRO_TYPES_AMOUNT] = rotation_table();
+
+const fn rotate_clockwise(shape: u16, size: i32) -> u16 {
+    let mut rotated = 0;
+
+    let mut i = 0;
+    while i < size * size {
+        let new_x = size - 1 - i / size;
+        let new_y = i % size;
+
+        rotated |= (shape >> i & 1) << (new_x + new_y * size);
+        i += 1;
+    }
+
+    rotated
+}
+
+const fn rotation_state(shape: u16, size: i32) -> RotationState {
+    let mut state = RotationState {
+        shape,
+        rows: [0; 4],
+        cells: [Cord(0, 0); 4],
+        min: Cord(size, size),
+        max: Cord(0, 0),
+    };
+
+    let mut cell = 0;
+    let mut i = 0;
+    while i < size * size {
+        if shape >> i & 1 == 1 {
+            let (x, y) = (i % size, i / size);
+
+            state.rows[y as usize] |= 1 << x;
+            state.cells[cell] = Cord(x, y);
+            cell += 1;
+
+            if x < state.min.0 { state.min.0 = x }
+            if y < state.min.1 { state.min.1 = y }
+            if x > state.max.0 { state.max.0 = x }
+            if y > state.max.1 { state.max.1 = y }
+        }
+        i += 1;
+    }
+
+    state
+}
+
+const fn rotation_table() -> [[RotationState; 4]; TETRO_TYPES_AMOUNT] {
+    let mut table = [[rotation_state(0, 0); 4]; TETRO_TYPES_AMOUNT];
 
-            shape = rotated_shape;
+    let mut tetro = 0;
+    while tetro < TETRO_TYPES_AMOUNT {
+        let (mut shape, size) = SPAWN_SHAPES[tetro];
+
+        let mut rotation = 0;
+        while rotation < 4 {
+            table[tetro][rotation] = rotation_state(shape, size);
+            shape = rotate_clockwise(shape, size);
+            rotation += 1;
         }
+        tetro += 1;
+    }
+
+    table
+}
+
+impl TetroType {
+    pub fn rotation_state(&self, rotation: usize) -> &'static RotationState {
+        &ROTATIONS[*self as usize][rotation % 4]
+    }
+
+    pub fn shape(&self, rotation: usize) -> u16 {
+        self.rotation_state(rotation).shape
+    }
 
-        shape
+    // the four occupied blocks relative to the top left of the shape
+    pub fn cells(&self, rotation: usize) -> [Cord; 4] {
+        self.rotation_state(rotation).cells
     }
 
     pub fn shape_size(&self) -> i32 {
@@ -86,21 +146,10 @@ impl TetroType {
         }
     }
 
-    pub fn start_pos(&self) -> Cord {
-        Cord((GAME_WIDTH as i32 - self.shape_size()) / 2, 0 )
-    }
-
     pub fn draw(&self, canvas: &mut Canvas<impl RenderTarget>, pos: Pos, rotation: usize, ghost: bool) {
-        let shape = self.shape(rotation);
-        let shape_size = self.shape_size();
-
         let border_size = BLOCK_SIZE / 10;
 
-        for i in 0..shape_size * shape_size {
-            let bit = shape >> i & 1;
-            if bit == 0 { continue; }
-
-            let relative_cord = Cord(i as i32 % shape_size, i as i32 / shape_size);
+        for relative_cord in self.cells(rotation) {
             let pos = Pos(
                 pos.0 + relative_cord.0 * BLOCK_SIZE,
                 pos.1 + relative_cord.1 * BLOCK_SIZE,
@@ -115,35 +164,35 @@ impl TetroType {
                 canvas.set_draw_color(self.colour().0);
             }
 
-            canvas.fill_rect(Rect::new(pos.0 + border_size as i32, pos.1 + border_size as i32, (BLOCK_SIZE - border_size * 2) as u32, (BLOCK_SIZE - border_size * 2) as u32)).unwrap();
+            canvas.fill_rect(Rect::new(pos.0 + border_size, pos.1 + border_size, (BLOCK_SIZE - border_size * 2) as u32, (BLOCK_SIZE - border_size * 2) as u32)).unwrap();
         }
     }
 
-    pub fn draw_centered(&self, canvas: &mut Canvas<impl RenderTarget>, origin_pos: Pos) {
+    pub fn draw_centered(&self, canvas: &mut Canvas<impl RenderTarget>, origin_pos: Pos, ghost: bool) {
         let center_offset = (4 - self.shape_size()) as f64 / 2. * BLOCK_SIZE as f64;
         let center_pos = Pos(origin_pos.0 + center_offset as i32, origin_pos.1 + center_offset as i32);
 
-        self.draw(canvas, center_pos, 0, false);
+        self.draw(canvas, center_pos, 0, ghost);
     }
 
-    pub fn random_tetro() -> TetroType {
-        let mut rng = thread_rng();
-
-        match rng.gen_range(0..=6) {
-            0 => TetroType::I,
-            1 => TetroType::J,
-            2 => TetroType::L,
-            3 => TetroType::O,
-            4 => TetroType::S,
-            5 => TetroType::T,
-            6 => TetroType::Z,
-            _ => panic!("invalid random value"),
+    pub fn from_index(index: usize) -> Option<TetroType> {
+        match index {
+            0 => Some(TetroType::I),
+            1 => Some(TetroType::J),
+            2 => Some(TetroType::L),
+            3 => Some(TetroType::O),
+            4 => Some(TetroType::S),
+            5 => Some(TetroType::T),
+            6 => Some(TetroType::Z),
+            _ => None,
         }
     }
 
-    pub fn random_set() -> Vec<TetroType> {
-        let mut rng = thread_rng();
+    pub fn random_tetro(rng: &mut impl Rng) -> TetroType {
+        TetroType::from_index(rng.gen_range(0..TETRO_TYPES_AMOUNT)).expect("invalid random value")
+    }
 
+    pub fn random_set(rng: &mut impl Rng) -> Vec<TetroType> {
         let mut set = vec![
             TetroType::I,
             TetroType::J,
@@ -154,7 +203,7 @@ impl TetroType {
             TetroType::Z,
         ];
 
-        set.shuffle(&mut rng);
+        set.shuffle(rng);
 
         set
     }
@@ -179,5 +228,10 @@ impl GameTetro {
     pub fn draw(&self, canvas: &mut Canvas<impl RenderTarget>, ghost: bool) {
         self.tetro_type.draw(canvas, self.cord.pos(), self.rotation, ghost);
     }
+
+    // the four blocks the tetro occupies on the board
+    pub fn cells(&self) -> [Cord; 4] {
+        self.tetro_type.cells(self.rotation).map(|cell| Cord(self.cord.0 + cell.0, self.cord.1 + cell.1))
+    }
 }
 
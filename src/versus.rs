@@ -0,0 +1,14 @@
+use crate::game::{Game, GameOverReason};
+
+// called every frame of a match, passes the garbage each game sends on to the other one
+// and ends the match in a win for whoever is left once the other one tops out
+pub fn update(first: &mut Game, second: &mut Game) {
+    second.pending_garbage.receive(first.take_outgoing_garbage());
+    first.pending_garbage.receive(second.take_outgoing_garbage());
+
+    if !first.is_playing && second.is_playing {
+        second.end_game(GameOverReason::Won);
+    } else if !second.is_playing && first.is_playing {
+        first.end_game(GameOverReason::Won);
+    }
+}